@@ -29,6 +29,10 @@ fn main() -> io::Result<()> {
     println!("cargo:rerun-if-changed=c/ak.h");
     println!("cargo:rerun-if-changed=c/utilities/default_streaming_mgr.h");
     println!("cargo:rerun-if-changed=c/utilities/default_streaming_mgr.cpp");
+    println!("cargo:rerun-if-changed=c/utilities/low_level_io.h");
+    println!("cargo:rerun-if-changed=c/utilities/low_level_io.cpp");
+    println!("cargo:rerun-if-changed=c/utilities/spatial_audio_settings.h");
+    println!("cargo:rerun-if-changed=c/utilities/spatial_audio_settings.cpp");
     println!("cargo:rerun-if-env-changed=WWISESDK");
     println!("cargo:rerun-if-env-changed=RRISE_RERUN_BUILD");
     // --- END RERUN CONFIG
@@ -127,7 +131,9 @@ fn main() -> io::Result<()> {
     build
         .cpp(true)
         .file(crate_dir.join("default_streaming_mgr.cpp"))
+        .file(crate_dir.join("low_level_io.cpp"))
         .file(crate_dir.join("static_plugins.cpp"))
+        .file(crate_dir.join("spatial_audio_settings.cpp"))
         .file(
             wwise_sdk
                 .join("samples")
@@ -190,6 +196,8 @@ fn main() -> io::Result<()> {
     let bindings = bindgen::Builder::default()
         .header("c/ak.h")
         .header("c/utilities/default_streaming_mgr.h")
+        .header("c/utilities/low_level_io.h")
+        .header("c/utilities/spatial_audio_settings.h")
         .clang_arg(format!(
             "-I{}",
             wwise_sdk
@@ -220,6 +228,17 @@ fn main() -> io::Result<()> {
         .allowlist_function("Ak.*")
         .allowlist_function("InitDefaultStreamMgr")
         .allowlist_function("TermDefaultStreamMgr")
+        .allowlist_function("AddDefaultBasePath")
+        .allowlist_function("IsStreamMgrInitialized")
+        .allowlist_function("DestroyStreamMgr")
+        .allowlist_function("LoadDefaultFilePackage")
+        .allowlist_function("UnloadDefaultFilePackage")
+        .allowlist_function("UnloadAllDefaultFilePackages")
+        .allowlist_function("InitCustomStreamMgr")
+        .allowlist_function("TermCustomStreamMgr")
+        .allowlist_type("RriseLowLevelIOCallbacks")
+        .allowlist_function("GetDefaultSpatialAudioInitSettings")
+        .allowlist_function("SetAudioInputCallbacks")
         .blocklist_item("AK_INVALID_GAME_OBJECT")
         .blocklist_item("AK_INVALID_AUDIO_OBJECT_ID")
         .rustified_enum("AKRESULT")
@@ -233,7 +252,11 @@ fn main() -> io::Result<()> {
         .rustified_enum("Ak3DSpatializationMode")
         .rustified_enum("AkPluginType")
         .rustified_enum("AkNodeType")
+        .rustified_enum("AkOpenMode")
         .rustified_enum("AK::SoundEngine::Query::RTPCValue_type")
+        .rustified_enum("AK::SoundEngine::AkActionOnEventType")
+        .rustified_enum("AK::SoundEngine::PreparationType")
+        .rustified_enum("AK::SoundEngine::AkBankContent")
         .bitfield_enum("AkAudioDeviceState")
         .bitfield_enum("AkBusHierarchyFlags")
         .bitfield_enum("AkMeteringFlags")
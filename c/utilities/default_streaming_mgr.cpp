@@ -5,23 +5,104 @@
 #include "default_streaming_mgr.h"
 #include <AkFilePackageLowLevelIOBlocking.h>
 
-static CAkFilePackageLowLevelIOBlocking g_lowLevelIO;
+// Heap-allocated so that it is created on init and destroyed on term: a static instance would keep
+// its device and file packages around between a term and a new init.
+static CAkFilePackageLowLevelIOBlocking* g_lowLevelIO = nullptr;
 
 AKRESULT InitDefaultStreamMgr(const AkDeviceSettings& deviceSettings, const AkOSChar* basePath)
 {
-    AKRESULT r = g_lowLevelIO.Init(deviceSettings);
-    if (r == AK_Success) {
-        g_lowLevelIO.SetBasePath(basePath);
-    }
+	if (g_lowLevelIO)
+	{
+		return AK_Fail;
+	}
+
+	g_lowLevelIO = new CAkFilePackageLowLevelIOBlocking();
+	AKRESULT r = g_lowLevelIO->Init(deviceSettings);
+	if (r == AK_Success)
+	{
+		r = g_lowLevelIO->SetBasePath(basePath);
+		if (r != AK_Success)
+		{
+			g_lowLevelIO->Term();
+		}
+	}
+
+	if (r != AK_Success)
+	{
+		delete g_lowLevelIO;
+		g_lowLevelIO = nullptr;
+	}
 
-	return g_lowLevelIO.Init(deviceSettings);
+	return r;
 }
 
 void TermDefaultStreamMgr()
 {
-	g_lowLevelIO.Term();
+	if (g_lowLevelIO)
+	{
+		g_lowLevelIO->Term();
+		delete g_lowLevelIO;
+		g_lowLevelIO = nullptr;
+	}
+
 	if (AK::IAkStreamMgr::Get())
 	{
 		AK::IAkStreamMgr::Get()->Destroy();
 	}
 }
+
+bool IsStreamMgrInitialized()
+{
+	return AK::IAkStreamMgr::Get() != nullptr;
+}
+
+void DestroyStreamMgr()
+{
+	// Only the Stream Manager: its devices, if any, are terminated by their own term function
+	if (AK::IAkStreamMgr::Get())
+	{
+		AK::IAkStreamMgr::Get()->Destroy();
+	}
+}
+
+AKRESULT AddDefaultBasePath(const AkOSChar* basePath)
+{
+	if (!g_lowLevelIO)
+	{
+		return AK_Fail;
+	}
+
+	// Base paths are searched most recently added first
+	return g_lowLevelIO->AddBasePath(basePath);
+}
+
+AKRESULT LoadDefaultFilePackage(const AkOSChar* packageName, AkUInt32* out_packageID)
+{
+	if (!g_lowLevelIO)
+	{
+		return AK_Fail;
+	}
+
+	// Packages are looked up before loose files when opening a file, most recently loaded first
+	return g_lowLevelIO->LoadFilePackage(packageName, *out_packageID);
+}
+
+AKRESULT UnloadDefaultFilePackage(AkUInt32 packageID)
+{
+	if (!g_lowLevelIO)
+	{
+		return AK_Fail;
+	}
+
+	return g_lowLevelIO->UnloadFilePackage(packageID);
+}
+
+AKRESULT UnloadAllDefaultFilePackages()
+{
+	if (!g_lowLevelIO)
+	{
+		return AK_Fail;
+	}
+
+	return g_lowLevelIO->UnloadAllFilePackages();
+}
@@ -9,5 +9,13 @@
 
 AKRESULT InitDefaultStreamMgr(const AkDeviceSettings& deviceSettings, const AkOSChar* basePath);
 void TermDefaultStreamMgr();
+AKRESULT AddDefaultBasePath(const AkOSChar* basePath);
+
+bool IsStreamMgrInitialized();
+void DestroyStreamMgr();
+
+AKRESULT LoadDefaultFilePackage(const AkOSChar* packageName, AkUInt32* out_packageID);
+AKRESULT UnloadDefaultFilePackage(AkUInt32 packageID);
+AKRESULT UnloadAllDefaultFilePackages();
 
 #endif // DEFAULT_STREAMING_MGR_H
@@ -0,0 +1,167 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+#include "low_level_io.h"
+#include <AK/Tools/Common/AkPlatformFuncs.h>
+
+// Blocking I/O hook and file location resolver forwarding everything to Rust callbacks.
+class CAkRustLowLevelIO : public AK::StreamMgr::IAkFileLocationResolver, public AK::StreamMgr::IAkIOHookBlocking
+{
+public:
+	CAkRustLowLevelIO(const RriseLowLevelIOCallbacks& callbacks)
+		: m_callbacks(callbacks)
+		, m_deviceID(AK_INVALID_DEVICE_ID)
+	{
+	}
+
+	AKRESULT Init(const AkDeviceSettings& deviceSettings)
+	{
+		if (deviceSettings.uSchedulerTypeFlags != AK_SCHEDULER_BLOCKING)
+		{
+			return AK_InvalidParameter;
+		}
+
+		m_deviceID = AK::StreamMgr::CreateDevice(deviceSettings, this);
+		if (m_deviceID == AK_INVALID_DEVICE_ID)
+		{
+			return AK_Fail;
+		}
+
+		AK::StreamMgr::SetFileLocationResolver(this);
+		return AK_Success;
+	}
+
+	void Term()
+	{
+		if (AK::StreamMgr::GetFileLocationResolver() == this)
+		{
+			AK::StreamMgr::SetFileLocationResolver(nullptr);
+		}
+
+		if (m_deviceID != AK_INVALID_DEVICE_ID)
+		{
+			AK::StreamMgr::DestroyDevice(m_deviceID);
+			m_deviceID = AK_INVALID_DEVICE_ID;
+		}
+	}
+
+	// IAkFileLocationResolver
+
+	AKRESULT Open(const AkOSChar* in_pszFileName, AkOpenMode in_eOpenMode, AkFileSystemFlags* in_pFlags, bool& io_bSyncOpen, AkFileDesc& io_fileDesc) override
+	{
+		return OpenFile(in_pszFileName, AK_INVALID_FILE_ID, in_eOpenMode, in_pFlags, io_bSyncOpen, io_fileDesc);
+	}
+
+	AKRESULT Open(AkFileID in_fileID, AkOpenMode in_eOpenMode, AkFileSystemFlags* in_pFlags, bool& io_bSyncOpen, AkFileDesc& io_fileDesc) override
+	{
+		return OpenFile(nullptr, in_fileID, in_eOpenMode, in_pFlags, io_bSyncOpen, io_fileDesc);
+	}
+
+	// IAkIOHookBlocking
+
+	AKRESULT Read(AkFileDesc& in_fileDesc, const AkIoHeuristics& /*in_heuristics*/, void* out_pBuffer, AkIOTransferInfo& io_transferInfo) override
+	{
+		return m_callbacks.read(m_callbacks.cookie, Handle(in_fileDesc), io_transferInfo.uFilePosition, out_pBuffer, io_transferInfo.uRequestedSize);
+	}
+
+	AKRESULT Write(AkFileDesc& in_fileDesc, const AkIoHeuristics& /*in_heuristics*/, void* in_pData, AkIOTransferInfo& io_transferInfo) override
+	{
+		return m_callbacks.write(m_callbacks.cookie, Handle(in_fileDesc), io_transferInfo.uFilePosition, in_pData, io_transferInfo.uRequestedSize);
+	}
+
+	AKRESULT Close(AkFileDesc& in_fileDesc) override
+	{
+		return m_callbacks.close(m_callbacks.cookie, Handle(in_fileDesc));
+	}
+
+	AkUInt32 GetBlockSize(AkFileDesc& in_fileDesc) override
+	{
+		return m_callbacks.blockSize(m_callbacks.cookie, Handle(in_fileDesc));
+	}
+
+	void GetDeviceDesc(AkDeviceDesc& out_deviceDesc) override
+	{
+#ifndef AK_OPTIMIZED
+		out_deviceDesc.deviceID = m_deviceID;
+		out_deviceDesc.bCanRead = true;
+		out_deviceDesc.bCanWrite = true;
+		AKPLATFORM::SafeStrCpy(out_deviceDesc.szDeviceName, AKTEXT("Rust Low-Level I/O"), AK_MONITOR_DEVICENAME_MAXLENGTH);
+		out_deviceDesc.uStringSize = (AkUInt32)AKPLATFORM::OsStrLen(out_deviceDesc.szDeviceName) + 1;
+#endif
+	}
+
+	AkUInt32 GetDeviceData() override
+	{
+		return 1;
+	}
+
+private:
+	AKRESULT OpenFile(const AkOSChar* in_pszFileName, AkFileID in_fileID, AkOpenMode in_eOpenMode, AkFileSystemFlags* in_pFlags, bool& io_bSyncOpen, AkFileDesc& io_fileDesc)
+	{
+		// Always open synchronously: the Rust callbacks are blocking anyway
+		io_bSyncOpen = true;
+
+		AkUIntPtr handle = 0;
+		AkInt64 fileSize = 0;
+		const bool isLanguageSpecific = in_pFlags && in_pFlags->bIsLanguageSpecific;
+		// Files opened without flags, by plug-ins for instance, are reported as banks
+		const AkCodecID codecID = in_pFlags ? in_pFlags->uCodecID : AKCODECID_BANK;
+		AKRESULT r = m_callbacks.open(m_callbacks.cookie, in_pszFileName, in_fileID, in_eOpenMode, codecID, isLanguageSpecific, &handle, &fileSize);
+		if (r == AK_Success)
+		{
+			io_fileDesc.iFileSize = fileSize;
+			io_fileDesc.uSector = 0;
+			io_fileDesc.uCustomParamSize = 0;
+			io_fileDesc.pCustomParam = reinterpret_cast<void*>(handle);
+			io_fileDesc.deviceID = m_deviceID;
+		}
+
+		return r;
+	}
+
+	static AkUIntPtr Handle(const AkFileDesc& in_fileDesc)
+	{
+		return reinterpret_cast<AkUIntPtr>(in_fileDesc.pCustomParam);
+	}
+
+	RriseLowLevelIOCallbacks m_callbacks;
+	AkDeviceID m_deviceID;
+};
+
+// Heap-allocated for the same reason as the default Low-Level I/O, see default_streaming_mgr.cpp.
+static CAkRustLowLevelIO* g_rustLowLevelIO = nullptr;
+
+AKRESULT InitCustomStreamMgr(const AkDeviceSettings& deviceSettings, const RriseLowLevelIOCallbacks& callbacks)
+{
+	if (g_rustLowLevelIO)
+	{
+		return AK_Fail;
+	}
+
+	g_rustLowLevelIO = new CAkRustLowLevelIO(callbacks);
+	AKRESULT r = g_rustLowLevelIO->Init(deviceSettings);
+	if (r != AK_Success)
+	{
+		g_rustLowLevelIO->Term();
+		delete g_rustLowLevelIO;
+		g_rustLowLevelIO = nullptr;
+	}
+
+	return r;
+}
+
+void TermCustomStreamMgr()
+{
+	if (g_rustLowLevelIO)
+	{
+		g_rustLowLevelIO->Term();
+		delete g_rustLowLevelIO;
+		g_rustLowLevelIO = nullptr;
+	}
+
+	if (AK::IAkStreamMgr::Get())
+	{
+		AK::IAkStreamMgr::Get()->Destroy();
+	}
+}
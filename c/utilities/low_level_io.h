@@ -0,0 +1,32 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+#ifndef LOW_LEVEL_IO_H
+#define LOW_LEVEL_IO_H
+
+#include <AK/SoundEngine/Common/AkStreamMgrModule.h>
+
+// Callbacks implementing the Low-Level I/O, called with the cookie of RriseLowLevelIOCallbacks.
+// Files are identified by an opaque handle given by the open callback. The file name is null when
+// opening by ID. The codec ID is AKCODECID_BANK for banks, or the codec of streamed media.
+typedef AKRESULT (*RriseLowLevelIOOpenFunc)(void* in_pCookie, const AkOSChar* in_pszFileName, AkFileID in_fileID, AkOpenMode in_eOpenMode, AkCodecID in_codecID, bool in_bIsLanguageSpecific, AkUIntPtr* out_pHandle, AkInt64* out_pFileSize);
+typedef AKRESULT (*RriseLowLevelIOReadFunc)(void* in_pCookie, AkUIntPtr in_handle, AkUInt64 in_uPosition, void* out_pBuffer, AkUInt32 in_uSize);
+typedef AKRESULT (*RriseLowLevelIOWriteFunc)(void* in_pCookie, AkUIntPtr in_handle, AkUInt64 in_uPosition, const void* in_pData, AkUInt32 in_uSize);
+typedef AKRESULT (*RriseLowLevelIOCloseFunc)(void* in_pCookie, AkUIntPtr in_handle);
+typedef AkUInt32 (*RriseLowLevelIOBlockSizeFunc)(void* in_pCookie, AkUIntPtr in_handle);
+
+struct RriseLowLevelIOCallbacks
+{
+	void* cookie;
+	RriseLowLevelIOOpenFunc open;
+	RriseLowLevelIOReadFunc read;
+	RriseLowLevelIOWriteFunc write;
+	RriseLowLevelIOCloseFunc close;
+	RriseLowLevelIOBlockSizeFunc blockSize;
+};
+
+AKRESULT InitCustomStreamMgr(const AkDeviceSettings& deviceSettings, const RriseLowLevelIOCallbacks& callbacks);
+void TermCustomStreamMgr();
+
+#endif // LOW_LEVEL_IO_H
@@ -0,0 +1,12 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+#include "spatial_audio_settings.h"
+
+void GetDefaultSpatialAudioInitSettings(AkSpatialAudioInitSettings& out_settings)
+{
+	// AkSpatialAudioInitSettings has no GetDefault function in the SDK: its defaults live in its
+	// inline constructor, which cannot be called from Rust.
+	out_settings = AkSpatialAudioInitSettings();
+}
@@ -0,0 +1,12 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+#ifndef SPATIAL_AUDIO_SETTINGS_H
+#define SPATIAL_AUDIO_SETTINGS_H
+
+#include <AK/SpatialAudio/Common/AkSpatialAudio.h>
+
+void GetDefaultSpatialAudioInitSettings(AkSpatialAudioInitSettings& out_settings);
+
+#endif // SPATIAL_AUDIO_SETTINGS_H
@@ -3,6 +3,7 @@
  */
 
 use rrise::{
+    engine::{Engine, EngineError},
     query_params::{get_rtpc_value, RtpcValueType},
     settings::*,
     sound_engine::*,
@@ -21,6 +22,8 @@ const DEFAULT_LISTENER_ID: AkGameObjectID = 1;
 const THE_GAME_OBJECT: AkGameObjectID = 100;
 
 fn main() -> Result<(), AkResult> {
+    let engine = init_sound_engine()?;
+
     // Run the Bevy app
     App::new()
         .add_plugins(DefaultPlugins)
@@ -42,7 +45,6 @@ fn main() -> Result<(), AkResult> {
         .insert_resource(match crossbeam_channel::unbounded() {
             (sender, receiver) => CallbackChannel { sender, receiver },
         })
-        .add_startup_system(init_sound_engine.pipe(system_adapter::unwrap))
         .add_startup_system_to_stage(
             StartupStage::PostStartup,
             setup_audio.pipe(system_adapter::unwrap),
@@ -63,7 +65,7 @@ fn main() -> Result<(), AkResult> {
     // Terminate Wwise
     stop_all(None);
     unregister_all_game_obj()?;
-    term_sound_engine()?;
+    engine.term();
 
     Ok(())
 }
@@ -303,64 +305,21 @@ fn spawn_meter(
     ));
 }
 
-#[cfg_attr(target_os = "linux", allow(unused_variables))]
-fn init_sound_engine() -> Result<(), AkResult> {
-    // init memorymgr
-    memory_mgr::init(&mut AkMemSettings::default())?;
-    assert!(memory_mgr::is_initialized());
-
-    // init streamingmgr
+fn init_sound_engine() -> Result<Engine, EngineError> {
     #[cfg(target_os = "windows")]
     let platform = "Windows";
     #[cfg(target_os = "linux")]
     let platform = "Linux";
-    stream_mgr::init_default_stream_mgr(
-        &AkStreamMgrSettings::default(),
-        &mut AkDeviceSettings::default(),
-        format!("examples/WwiseProject/GeneratedSoundBanks/{}", platform),
-    )?;
-    stream_mgr::set_current_language("English(US)")?;
-
-    // init soundengine
-    {
-        let mut platform_settings = AkPlatformInitSettings::default();
-        sound_engine::init(&mut setup_example_dll_path(), &mut platform_settings)?;
-    }
-
-    // init musicengine
-    music_engine::init(&mut settings::AkMusicSettings::default())?;
-
-    // init comms
-    #[cfg(not(wwrelease))]
-    communication::init(&AkCommSettings::default())?;
-
-    if !is_initialized() {
-        panic!("Unknown error: the sound engine didn't initialize properly");
-    }
-
-    Ok(())
-}
-
-fn term_sound_engine() -> Result<(), AkResult> {
-    // term comms
-    #[cfg(not(wwrelease))]
-    communication::term();
-
-    // term spatial
-
-    // term music
-    music_engine::term();
 
-    // term soundengine
-    sound_engine::term();
-
-    // term streamingmgr
-    stream_mgr::term_default_stream_mgr();
-
-    // term memorymgr
-    memory_mgr::term();
-
-    Ok(())
+    // no need for spatial
+    Engine::builder(format!(
+        "examples/WwiseProject/GeneratedSoundBanks/{}",
+        platform
+    ))
+    .with_language("English(US)")
+    .with_init_settings(setup_example_dll_path())
+    .with_music(AkMusicSettings::default())
+    .init()
 }
 
 fn setup_example_dll_path() -> AkInitSettings {
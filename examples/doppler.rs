@@ -2,6 +2,8 @@
  * Copyright (c) 2022 Contributors to the Rrise project
  */
 
+use rrise::engine::{Engine, EngineError};
+use rrise::game_object::GameObject;
 use rrise::settings::*;
 use rrise::{game_syncs::SetRtpcValue, sound_engine::*, *};
 
@@ -16,8 +18,6 @@ use std::time::Duration;
 use cc;
 
 const SPEED_OF_SOUND: f32 = 340_f32;
-const DEFAULT_LISTENER_ID: AkGameObjectID = 1;
-const THE_GAME_OBJECT: AkGameObjectID = 100;
 
 // If you play with those, you might want to adapt the attenuation curve in the Wwise project
 const TRAJECTORY_LENGTH: f32 = 90_f32; // expected to be positive
@@ -34,16 +34,16 @@ fn main() -> Result<(), AkResult> {
     })
     .expect("Error setting Ctrl-C handler");
 
-    init_sound_engine()?;
+    let engine = init_sound_engine()?;
 
     if !is_initialized() {
         panic!("Unknown error: the sound engine didn't initialize properly");
     }
 
-    register_game_obj(DEFAULT_LISTENER_ID)?;
-    add_default_listener(DEFAULT_LISTENER_ID)?;
+    let listener = GameObject::named("Listener")?;
+    listener.make_default_listener()?;
 
-    register_game_obj(THE_GAME_OBJECT)?;
+    let the_game_object = GameObject::named("TheGameObject")?;
 
     if let Err(akr) = load_bank_by_name("Init.bnk") {
         panic!("Couldn't load initbank: {}", akr);
@@ -53,7 +53,7 @@ fn main() -> Result<(), AkResult> {
         panic!("Couldn't load thebank: {}", akr);
     }
 
-    if let Ok(playing_id) = PostEvent::new(THE_GAME_OBJECT, "PlayDoppler").post() {
+    if let Ok(playing_id) = the_game_object.post_event("PlayDoppler").post() {
         println!("Successfully started event with playingID {}", playing_id)
     } else {
         panic!("Couldn't post event");
@@ -71,7 +71,7 @@ fn main() -> Result<(), AkResult> {
     let half_period = TRAJECTORY_LENGTH / TRAJECTORY_SPEED;
     let mut last_t = 0_f32;
     let mut direction = 1_f32;
-    let mut rtpc_conf = SetRtpcValue::new("Doppler", 0.).for_target(THE_GAME_OBJECT);
+    let mut rtpc_conf = SetRtpcValue::new("Doppler", 0.).for_target(the_game_object.id());
     loop {
         let app_time = instant.elapsed().as_secs_f32();
 
@@ -90,7 +90,7 @@ fn main() -> Result<(), AkResult> {
         }
 
         let new_p = (direction * -half_length).lerp(direction * half_length, t);
-        set_position(THE_GAME_OBJECT, AkTransform::from([new_p, 0., 2.]))?;
+        the_game_object.set_position(AkTransform::from([new_p, 0., 2.]))?;
 
         // Doppler effect computation: because the movement is 1D and the listener doesn't move,
         // computation is simplified greatly!
@@ -108,64 +108,25 @@ fn main() -> Result<(), AkResult> {
         last_t = t;
     }
 
-    term_sound_engine()?;
+    engine.term();
 
     Ok(())
 }
 
-fn init_sound_engine() -> Result<(), AkResult> {
-    // init memorymgr
-    memory_mgr::init(&mut AkMemSettings::default())?;
-    assert!(memory_mgr::is_initialized());
-
-    // init streamingmgr
+fn init_sound_engine() -> Result<Engine, EngineError> {
     #[cfg(target_os = "windows")]
     let platform = "Windows";
     #[cfg(target_os = "linux")]
     let platform = "Linux";
-    stream_mgr::init_default_stream_mgr(
-        &AkStreamMgrSettings::default(),
-        &mut AkDeviceSettings::default(),
-        format!("examples/WwiseProject/GeneratedSoundBanks/{}", platform),
-    )?;
-    stream_mgr::set_current_language("English(US)")?;
-
-    // init soundengine
-    sound_engine::init(
-        &mut setup_example_dll_path(),
-        &mut AkPlatformInitSettings::default(),
-    )?;
-
-    // no need for music engine
-
-    // no need for spatial
-
-    // init comms
-    #[cfg(not(wwrelease))]
-    communication::init(&AkCommSettings::default())?;
-
-    Ok(())
-}
 
-fn term_sound_engine() -> Result<(), AkResult> {
-    // term comms
-    #[cfg(not(wwrelease))]
-    communication::term();
-
-    // term spatial
-
-    // term music
-
-    // term soundengine
-    sound_engine::term();
-
-    // term streamingmgr
-    stream_mgr::term_default_stream_mgr();
-
-    // term memorymgr
-    memory_mgr::term();
-
-    Ok(())
+    // no need for music engine nor spatial
+    Engine::builder(format!(
+        "examples/WwiseProject/GeneratedSoundBanks/{}",
+        platform
+    ))
+    .with_language("English(US)")
+    .with_init_settings(setup_example_dll_path())
+    .init()
 }
 
 fn setup_example_dll_path() -> AkInitSettings {
@@ -2,6 +2,8 @@
  * Copyright (c) 2022 Contributors to the Rrise project
  */
 
+use rrise::engine::{Engine, EngineError};
+use rrise::game_object::GameObject;
 use rrise::settings::*;
 use rrise::{sound_engine::*, *};
 
@@ -15,9 +17,6 @@ use std::time::Duration;
 #[cfg(windows)]
 use cc;
 
-const DEFAULT_LISTENER_ID: AkGameObjectID = 1;
-const THE_GAME_OBJECT: AkGameObjectID = 100;
-
 fn main() -> Result<(), AkResult> {
     SimpleLogger::new().init().unwrap();
     let should_stop = Arc::new(AtomicBool::new(false));
@@ -28,16 +27,16 @@ fn main() -> Result<(), AkResult> {
     })
     .expect("Error setting Ctrl-C handler");
 
-    init_sound_engine()?;
+    let engine = init_sound_engine()?;
 
     if !is_initialized() {
         panic!("Unknown error: the sound engine didn't initialize properly");
     }
 
-    register_game_obj(DEFAULT_LISTENER_ID)?;
-    add_default_listener(DEFAULT_LISTENER_ID)?;
+    let listener = GameObject::named("Listener")?;
+    listener.make_default_listener()?;
 
-    register_game_obj(THE_GAME_OBJECT)?;
+    let the_game_object = GameObject::named("TheGameObject")?;
 
     if let Err(akr) = load_bank_by_name("Init.bnk") {
         panic!("Couldn't load initbank: {}", akr);
@@ -47,7 +46,7 @@ fn main() -> Result<(), AkResult> {
         panic!("Couldn't load thebank: {}", akr);
     }
 
-    if let Ok(playing_id) = PostEvent::new(THE_GAME_OBJECT, 2586140731).post() {
+    if let Ok(playing_id) = the_game_object.post_event(2586140731).post() {
         println!("Successfully started event with playingID {}", playing_id)
     } else {
         panic!("Couldn't post event");
@@ -69,70 +68,31 @@ fn main() -> Result<(), AkResult> {
 
         // move sound source from -3 to 3 along X axis over the sound length (0.974s), repeatedly
         let new_p = (-3.).lerp(3., app_time % 0.974);
-        set_position(THE_GAME_OBJECT, AkTransform::from([new_p, 0., 0.]))?;
+        the_game_object.set_position(AkTransform::from([new_p, 0., 0.]))?;
 
         // simulate ~60 frames per second
         std::thread::sleep(Duration::from_millis(1000 / 60));
     }
 
-    term_sound_engine()?;
+    engine.term();
 
     Ok(())
 }
 
-fn init_sound_engine() -> Result<(), AkResult> {
-    // init memorymgr
-    memory_mgr::init(&mut AkMemSettings::default())?;
-    assert!(memory_mgr::is_initialized());
-
-    // init streamingmgr
+fn init_sound_engine() -> Result<Engine, EngineError> {
     #[cfg(target_os = "windows")]
     let platform = "Windows";
     #[cfg(target_os = "linux")]
     let platform = "Linux";
-    stream_mgr::init_default_stream_mgr(
-        &AkStreamMgrSettings::default(),
-        &mut AkDeviceSettings::default(),
-        format!("examples/WwiseProject/GeneratedSoundBanks/{}", platform),
-    )?;
-    stream_mgr::set_current_language("English(US)")?;
-
-    // init soundengine
-    sound_engine::init(
-        &mut setup_example_dll_path(),
-        &mut AkPlatformInitSettings::default(),
-    )?;
-
-    // no need for music engine
-
-    // no need for spatial
-
-    // init comms
-    #[cfg(not(wwrelease))]
-    communication::init(&AkCommSettings::default())?;
-
-    Ok(())
-}
-
-fn term_sound_engine() -> Result<(), AkResult> {
-    // term comms
-    #[cfg(not(wwrelease))]
-    communication::term();
-
-    // term spatial
-
-    // term music
 
-    // term soundengine
-    sound_engine::term();
-
-    // term streamingmgr
-    stream_mgr::term_default_stream_mgr();
-
-    // term memorymgr
-    memory_mgr::term();
-
-    Ok(())
+    // no need for music engine nor spatial
+    Engine::builder(format!(
+        "examples/WwiseProject/GeneratedSoundBanks/{}",
+        platform
+    ))
+    .with_language("English(US)")
+    .with_init_settings(setup_example_dll_path())
+    .init()
 }
 
 fn setup_example_dll_path() -> AkInitSettings {
@@ -274,6 +274,43 @@ fn main() {
         out.write_all("}\n".as_bytes())
             .expect("Failed to write headers");
     }
+
+    // ---- WRITE EMBEDDED FILES
+    if env::var("CARGO_FEATURE_EMBED").is_ok() {
+        out.write_all("\npub mod embedded {\n".as_bytes())
+            .expect("Failed to write headers");
+        out.write_all("\tpub const FILES: &[(&str, &[u8])] = &[\n".as_bytes())
+            .expect("Failed to write headers");
+
+        let bnk_ext: &OsStr = OsStr::new("bnk");
+        let wem_ext: &OsStr = OsStr::new("wem");
+        for entry in WalkDir::new(bank_path.clone()).max_depth(2).into_iter() {
+            let entry = entry.unwrap_or_else(|e| {
+                panic!("Couldn't walk {} - {}", bank_path.to_str().unwrap(), e)
+            });
+            let path = entry.path();
+            if !matches!(path.extension(), Some(ext) if ext == bnk_ext || ext == wem_ext) {
+                continue;
+            }
+
+            println!("cargo:rerun-if-changed={}", path.to_str().unwrap());
+            let relative_path = path
+                .strip_prefix(&bank_path)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .replace('\\', "/");
+            out.write_fmt(format_args!(
+                "\t\t({:?}, include_bytes!({:?})),\n",
+                relative_path,
+                path.to_str().unwrap()
+            ))
+            .expect("Failed to write headers");
+        }
+
+        out.write_all("\t];\n}\n".as_bytes())
+            .expect("Failed to write headers");
+    }
 }
 
 fn to_rust_name(name: &str) -> String {
@@ -0,0 +1,301 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Feed samples generated in Rust to the Audio Input source plug-in.
+//!
+//! Only available with the `AkAudioInputSource` feature, which links the plug-in statically.
+//!
+//! An event playing an Audio Input source is posted with [post_audio_input] along with an
+//! [AudioInput] implementation; the plug-in then pulls its format and samples from it on the audio
+//! thread, until the event ends.
+
+use crate::bindings::root::{
+    AkAudioBuffer, AkAudioFormat, AkChannelConfigType_AK_ChannelConfigType_Anonymous,
+    AkChannelConfigType_AK_ChannelConfigType_Standard, SetAudioInputCallbacks,
+    AK_SPEAKER_SETUP_MONO, AK_SPEAKER_SETUP_STEREO,
+};
+use crate::sound_engine::{PlayingHandle, PostEvent};
+use crate::{AkCallbackInfo, AkCallbackType, AkPlayingID, AkReal32, AkResult, AkUInt32};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, Once};
+use std::time::{Duration, Instant};
+
+/// An [AudioInput] and whether its event ended.
+///
+/// Its mutex is only locked by the audio thread, never by the thread posting the event.
+struct InputSlot {
+    input: Mutex<Box<dyn AudioInput>>,
+    ended: AtomicBool,
+}
+
+static INSTALL_CALLBACKS: Once = Once::new();
+/// Locked briefly to register and unregister the inputs, never while calling them.
+static AUDIO_INPUTS: Mutex<Option<HashMap<AkPlayingID, Arc<InputSlot>>>> = Mutex::new(None);
+/// Number of events being posted whose input isn't registered yet.
+static PENDING_POSTS: AtomicUsize = AtomicUsize::new(0);
+/// Longest time the plug-in waits in [get_format] for the input of an event being posted to be
+/// registered. Posting can itself wait for the audio thread when the message queue is full.
+const REGISTRATION_TIMEOUT: Duration = Duration::from_millis(10);
+
+thread_local! {
+    /// The inputs already looked up by an audio thread, so that it doesn't need to lock
+    /// [AUDIO_INPUTS] again for them.
+    static LOOKED_UP: RefCell<HashMap<AkPlayingID, Arc<InputSlot>>> = RefCell::new(HashMap::new());
+}
+
+/// Source of samples for an instance of the Audio Input source plug-in.
+///
+/// All methods are called on the audio thread: they must return quickly and should not allocate
+/// or block, or audio might stutter.
+pub trait AudioInput: Send + 'static {
+    /// Number of channels of the samples given by [fill](AudioInput::fill), at the sample rate of
+    /// the sound engine `sample_rate`.
+    ///
+    /// Called once, before the first call to [fill](AudioInput::fill). 1 and 2 channels are
+    /// respectively mono and stereo; other channel counts are anonymous configurations.
+    ///
+    /// *Remark* The plug-in starts right after the event is posted, possibly before
+    /// [post_audio_input] registers the input; it then waits for it a few milliseconds at most.
+    /// Past that, which can happen when the message queue of the sound engine is full, this is not
+    /// called and the source plays in mono.
+    ///
+    /// Default: `1`
+    fn channels(&mut self, _sample_rate: AkUInt32) -> AkUInt32 {
+        1
+    }
+
+    /// Fill `buffer` with the next samples.
+    ///
+    /// *Return* `false` when there are no more samples to play after this buffer.
+    fn fill(&mut self, buffer: &mut AudioInputBuffer) -> bool;
+
+    /// Gain applied to the samples.
+    ///
+    /// Default: `1`
+    fn gain(&mut self) -> AkReal32 {
+        1.
+    }
+}
+
+/// Deinterleaved 32 bits float samples to be filled by an [AudioInput].
+pub struct AudioInputBuffer<'a> {
+    buffer: &'a mut AkAudioBuffer,
+}
+
+impl<'a> AudioInputBuffer<'a> {
+    /// Number of channels of the buffer.
+    pub fn num_channels(&self) -> usize {
+        self.buffer.channelConfig.uNumChannels() as usize
+    }
+
+    /// Number of frames (samples per channel) of the buffer.
+    pub fn num_frames(&self) -> usize {
+        self.buffer.uMaxFrames as usize
+    }
+
+    /// The samples of `channel`.
+    ///
+    /// Panics if `channel` is out of range.
+    pub fn channel_mut(&mut self, channel: usize) -> &mut [f32] {
+        assert!(channel < self.num_channels(), "channel out of range");
+        let frames = self.num_frames();
+        // Safety
+        // The audio input plug-in gives non-interleaved float buffers of uMaxFrames per channel
+        unsafe {
+            std::slice::from_raw_parts_mut(
+                (self.buffer.pData as *mut f32).add(channel * frames),
+                frames,
+            )
+        }
+    }
+}
+
+/// Post an event playing an Audio Input source, fed by `input`.
+///
+/// `input` is dropped once the event ends. If the event plays more than one Audio Input source,
+/// they all share `input`.
+///
+/// The audio thread only locks the registry of inputs the first time it looks `input` up: calls to
+/// `input` never wait for the thread posting or stopping events.
+///
+/// *Remark* The callbacks of `event` are replaced by the one needed to drop `input`; use
+/// [post_audio_input_with_callback] to get them.
+///
+/// *Return* The [PlayingHandle] of the event, or [AK_Fail](AkResult::AK_Fail) if it could not be posted.
+pub fn post_audio_input<I: AudioInput>(
+    event: &PostEvent,
+    input: I,
+) -> Result<PlayingHandle, AkResult> {
+    post_audio_input_with_callback(event, input, |_| {})
+}
+
+/// Same as [post_audio_input], also calling `callback` like
+/// [PostEvent::post_with_callback] would.
+pub fn post_audio_input_with_callback<I, F>(
+    event: &PostEvent,
+    input: I,
+    mut callback: F,
+) -> Result<PlayingHandle, AkResult>
+where
+    I: AudioInput,
+    F: FnMut(AkCallbackInfo) + 'static,
+{
+    INSTALL_CALLBACKS.call_once(|| unsafe {
+        SetAudioInputCallbacks(Some(execute), Some(get_format), Some(get_gain));
+    });
+
+    let slot = Arc::new(InputSlot {
+        input: Mutex::new(Box::new(input)),
+        ended: AtomicBool::new(false),
+    });
+
+    // Until `slot` is registered, the plug-in gets silence instead of an end of data for the
+    // inputs it can't find.
+    PENDING_POSTS.fetch_add(1, Ordering::SeqCst);
+    let ending_slot = slot.clone();
+    let posted = event.post_with_callback(move |info| {
+        if let AkCallbackInfo::Event {
+            callback_type,
+            playing_id,
+            ..
+        } = info
+        {
+            if callback_type.contains(AkCallbackType::AK_EndOfEvent) {
+                ending_slot.ended.store(true, Ordering::SeqCst);
+                unregister(playing_id);
+            }
+        }
+        callback(info)
+    });
+
+    if let Ok(handle) = &posted {
+        let playing_id = handle.playing_id();
+        AUDIO_INPUTS
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .insert(playing_id, slot.clone());
+        // The event may have ended before its input was registered
+        if slot.ended.load(Ordering::SeqCst) {
+            unregister(playing_id);
+        }
+    }
+    PENDING_POSTS.fetch_sub(1, Ordering::SeqCst);
+
+    posted
+}
+
+fn unregister(playing_id: AkPlayingID) {
+    if let Some(inputs) = AUDIO_INPUTS.lock().unwrap().as_mut() {
+        inputs.remove(&playing_id);
+    }
+    let _ = LOOKED_UP.try_with(|looked_up| {
+        if let Ok(mut looked_up) = looked_up.try_borrow_mut() {
+            looked_up.remove(&playing_id);
+        }
+    });
+}
+
+/// The input of `playing_id`, looked up in [AUDIO_INPUTS] only the first time this thread asks
+/// for it.
+fn lookup(playing_id: AkPlayingID) -> Option<Arc<InputSlot>> {
+    LOOKED_UP.with(|looked_up| {
+        let mut looked_up = looked_up.borrow_mut();
+        if let Some(slot) = looked_up.get(&playing_id) {
+            return Some(slot.clone());
+        }
+
+        let slot = AUDIO_INPUTS
+            .lock()
+            .unwrap()
+            .as_ref()?
+            .get(&playing_id)?
+            .clone();
+        // Forget the inputs whose event ended on another thread
+        looked_up.retain(|_, slot| !slot.ended.load(Ordering::SeqCst));
+        looked_up.insert(playing_id, slot.clone());
+        Some(slot)
+    })
+}
+
+unsafe extern "C" fn execute(playing_id: AkPlayingID, buffer: *mut AkAudioBuffer) {
+    let buffer = &mut *buffer;
+    let slot = lookup(playing_id);
+    let more = match slot.as_ref().map(|slot| slot.input.try_lock()) {
+        Some(Ok(mut input)) => Some(input.fill(&mut AudioInputBuffer { buffer })),
+        // Only the audio threads lock the input: skip this buffer rather than wait
+        Some(Err(_)) => None,
+        None if PENDING_POSTS.load(Ordering::SeqCst) > 0 => None,
+        None => {
+            buffer.uValidFrames = 0;
+            buffer.eState = AkResult::AK_NoMoreData;
+            return;
+        }
+    };
+
+    if more.is_none() {
+        let samples = buffer.channelConfig.uNumChannels() as usize * buffer.uMaxFrames as usize;
+        std::ptr::write_bytes(buffer.pData as *mut f32, 0, samples);
+    }
+    buffer.uValidFrames = buffer.uMaxFrames;
+    buffer.eState = if more.unwrap_or(true) {
+        AkResult::AK_DataReady
+    } else {
+        AkResult::AK_NoMoreData
+    };
+}
+
+/// The input of `playing_id`, waiting for it to be registered if events are being posted.
+fn lookup_registered(playing_id: AkPlayingID) -> Option<Arc<InputSlot>> {
+    let deadline = Instant::now() + REGISTRATION_TIMEOUT;
+    loop {
+        // Read before looking up: inputs are registered before the count goes down
+        let pending = PENDING_POSTS.load(Ordering::SeqCst) > 0;
+        if let Some(slot) = lookup(playing_id) {
+            return Some(slot);
+        }
+        if !pending || Instant::now() >= deadline {
+            return None;
+        }
+        std::thread::yield_now();
+    }
+}
+
+unsafe extern "C" fn get_format(playing_id: AkPlayingID, format: *mut AkAudioFormat) {
+    let format = &mut *format;
+    // The format is only asked once: wait for the input instead of using the default one
+    let slot = match lookup_registered(playing_id) {
+        Some(slot) => slot,
+        None => return,
+    };
+    let channels = match slot.input.try_lock() {
+        Ok(mut input) => input.channels(format.uSampleRate),
+        Err(_) => return,
+    };
+
+    let config = &mut format.channelConfig;
+    config.set_uNumChannels(channels);
+    match channels {
+        1 => {
+            config.set_eConfigType(AkChannelConfigType_AK_ChannelConfigType_Standard);
+            config.set_uChannelMask(AK_SPEAKER_SETUP_MONO);
+        }
+        2 => {
+            config.set_eConfigType(AkChannelConfigType_AK_ChannelConfigType_Standard);
+            config.set_uChannelMask(AK_SPEAKER_SETUP_STEREO);
+        }
+        _ => {
+            config.set_eConfigType(AkChannelConfigType_AK_ChannelConfigType_Anonymous);
+            config.set_uChannelMask(0);
+        }
+    }
+}
+
+unsafe extern "C" fn get_gain(playing_id: AkPlayingID) -> AkReal32 {
+    lookup(playing_id)
+        .and_then(|slot| slot.input.try_lock().ok().map(|mut input| input.gain()))
+        .unwrap_or(1.)
+}
@@ -0,0 +1,604 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Reference-counted handles over loaded SoundBanks.
+//!
+//! Loading the same bank (by name, by ID or from memory) through several [Bank] handles shares it;
+//! it is unloaded when the last handle is dropped, as many times as the sound engine loaded it (for
+//! instance when two asynchronous loads of the bank were in flight at the same time).
+//!
+//! *Remark* [sound_engine::clear_banks] and [sound_engine::term] unload every bank: the handles
+//! still alive at that point don't unload anything anymore when dropped.
+
+use crate::sound_engine::{
+    self, get_id_from_string, load_bank_by_id, load_bank_by_id_async, load_bank_by_name,
+    load_bank_by_name_async, load_bank_memory_copy, load_bank_memory_copy_async,
+    load_bank_memory_view_async_unchecked, load_bank_memory_view_unchecked, unload_bank_by_id,
+    BankCallbackResult, BankMemory,
+};
+use crate::{AkBankID, AkID, AkResult};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum BankState {
+    /// A thread is loading the bank, or reloading it.
+    Loading,
+    Loaded,
+    /// A thread is unloading the bank.
+    Unloading,
+    /// The bank couldn't be reloaded, but handles to it are still alive.
+    Unloaded,
+}
+
+struct BankEntry {
+    ref_count: usize,
+    name: Option<String>,
+    state: BankState,
+    /// One per sound engine load of the bank, with the buffer it was loaded from in-place: each
+    /// must be unloaded once.
+    loads: Vec<Option<BankMemory>>,
+}
+
+impl BankEntry {
+    fn new(state: BankState) -> Self {
+        Self {
+            ref_count: 0,
+            name: None,
+            state,
+            loads: Vec::new(),
+        }
+    }
+}
+
+type Banks = Option<HashMap<AkBankID, BankEntry>>;
+
+/// The banks loaded through [Bank] handles. The sound engine is never called with this lock held,
+/// as bank callbacks lock it from the Bank Manager thread.
+static BANKS: Mutex<Banks> = Mutex::new(None);
+/// Notified whenever a bank is done loading or unloading.
+static BANKS_CHANGED: Condvar = Condvar::new();
+
+/// A loaded SoundBank, unloaded when the last handle to it is dropped.
+///
+/// Cloning a [Bank] shares the same load.
+///
+/// *See also*
+/// > - [sound_engine::load_bank_by_name]
+/// > - [sound_engine::load_bank_memory_view]
+/// > - [sound_engine::load_bank_memory_copy]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Bank {
+    id: AkBankID,
+}
+
+impl Bank {
+    /// Load a bank synchronously by name or by ID, or share it if it is already loaded through
+    /// another [Bank].
+    ///
+    /// *Return* See [sound_engine::load_bank_by_name] and [sound_engine::load_bank_by_id].
+    pub fn load<'a, T: Into<AkID<'a>>>(bank: T) -> Result<Self, AkResult> {
+        match bank.into() {
+            AkID::Name(name) => {
+                let id = bank_id_from_name(name);
+                acquire(Some(id), Some(name), None, |_| load_bank_by_name(name))
+            }
+            AkID::ID(id) => acquire(Some(id), None, None, |_| load_bank_by_id(id)),
+        }
+    }
+
+    /// Load a bank synchronously from `bank`, in-place.
+    ///
+    /// `bank` is either a `'static` buffer, such as one embedded with
+    /// [include_bank!](crate::include_bank), or a [BankMemory] read at runtime; it is kept alive
+    /// until the bank is unloaded.
+    ///
+    /// *Return* See [sound_engine::load_bank_memory_view].
+    pub fn load_memory_view<T: Into<BankMemory>>(bank: T) -> Result<Self, AkResult> {
+        // The buffer is kept in the bank entry until the bank is unloaded
+        acquire(None, None, Some(bank.into()), |bank| unsafe {
+            load_bank_memory_view_unchecked(bank.unwrap())
+        })
+    }
+
+    /// Load a bank synchronously from a copy of `bank`.
+    ///
+    /// *Return* See [sound_engine::load_bank_memory_copy].
+    pub fn load_memory_copy(bank: &[u8]) -> Result<Self, AkResult> {
+        acquire(None, None, None, |_| load_bank_memory_copy(bank))
+    }
+
+    /// Load a bank asynchronously by name or by ID, or share it if it is already loaded through
+    /// another [Bank].
+    ///
+    /// *Return* A [BankLoad] future resolving to the [Bank] once it is loaded, or the error of the
+    /// load request.
+    ///
+    /// *See also*
+    /// > - [sound_engine::load_bank_by_name_async]
+    /// > - [sound_engine::load_bank_by_id_async]
+    pub fn load_async<'a, T: Into<AkID<'a>>>(bank: T) -> BankLoad {
+        let bank = bank.into();
+        let (id, name) = match bank {
+            AkID::Name(name) => (bank_id_from_name(name), Some(name.to_string())),
+            AkID::ID(id) => (id, None),
+        };
+        if let Some(bank) = try_share(id) {
+            return BankLoad::ready(Ok(bank));
+        }
+
+        let (load, on_loaded) = BankLoad::pending(name, None);
+        let request = match bank {
+            AkID::Name(name) => load_bank_by_name_async(name, on_loaded).map(|_| ()),
+            AkID::ID(id) => load_bank_by_id_async(id, on_loaded),
+        };
+        match request {
+            Ok(()) => load,
+            Err(akr) => BankLoad::ready(Err(akr)),
+        }
+    }
+
+    /// Load a bank asynchronously from `bank`, in-place.
+    ///
+    /// `bank` is kept alive until the bank is unloaded, or failed to load: see
+    /// [load_memory_view](Self::load_memory_view).
+    ///
+    /// *See also* [sound_engine::load_bank_memory_view_async]
+    pub fn load_memory_view_async<T: Into<BankMemory>>(bank: T) -> BankLoad {
+        let bank = bank.into();
+        let (ptr, len) = (bank.as_ptr(), bank.len());
+        let (load, on_loaded) = BankLoad::pending(None, Some(bank));
+        // The buffer is kept alive by the callback, then by the bank entry
+        match unsafe {
+            load_bank_memory_view_async_unchecked(std::slice::from_raw_parts(ptr, len), on_loaded)
+        } {
+            Ok(_) => load,
+            Err(akr) => BankLoad::ready(Err(akr)),
+        }
+    }
+
+    /// Load a bank asynchronously from a copy of `bank`.
+    ///
+    /// *See also* [sound_engine::load_bank_memory_copy_async]
+    pub fn load_memory_copy_async(bank: Vec<u8>) -> BankLoad {
+        let (load, on_loaded) = BankLoad::pending(None, None);
+        match load_bank_memory_copy_async(bank, on_loaded) {
+            Ok(_) => load,
+            Err(akr) => BankLoad::ready(Err(akr)),
+        }
+    }
+
+    /// The ID of the bank.
+    pub fn id(&self) -> AkBankID {
+        self.id
+    }
+
+    /// The name of the bank, if it was loaded by name.
+    pub fn name(&self) -> Option<String> {
+        with_entry(self.id, |entry| entry.name.clone()).flatten()
+    }
+
+    /// Whether the bank is loaded.
+    ///
+    /// A bank stays unloaded, while its handles stay valid, when it couldn't be loaded again by a
+    /// [BankWatcher](crate::hot_reload::BankWatcher) or a
+    /// [LanguageManager](crate::language::LanguageManager), until it is reloaded successfully.
+    pub fn is_loaded(&self) -> bool {
+        is_loaded(self.id)
+    }
+
+    /// Number of [Bank] handles sharing this bank.
+    pub fn ref_count(&self) -> usize {
+        with_entry(self.id, |entry| entry.ref_count).unwrap_or(0)
+    }
+
+    /// Release this handle, unloading the bank if it was the last one.
+    ///
+    /// Same as dropping the handle, except that an error while unloading is returned instead of
+    /// logged.
+    ///
+    /// *Return* See [sound_engine::unload_bank_by_id].
+    pub fn unload(self) -> Result<(), AkResult> {
+        let id = self.id;
+        std::mem::forget(self);
+        release(id)
+    }
+}
+
+impl Clone for Bank {
+    fn clone(&self) -> Self {
+        with_entry(self.id, |entry| entry.ref_count += 1);
+        Self { id: self.id }
+    }
+}
+
+impl Display for Bank {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.name() {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{}", self.id),
+        }
+    }
+}
+
+impl Drop for Bank {
+    fn drop(&mut self) {
+        if let Err(akr) = release(self.id) {
+            log::warn!("Couldn't unload bank {}: {}", self.id, akr);
+        }
+    }
+}
+
+struct BankLoadState {
+    result: Option<Result<Bank, AkResult>>,
+    waker: Option<Waker>,
+    abandoned: bool,
+}
+
+/// Future resolving to a [Bank] once it has been loaded asynchronously.
+///
+/// The bank is loaded on the Bank Manager thread whether the future is polled or not; dropping the
+/// future before it resolves unloads the bank once it is loaded.
+///
+/// *See also* [Bank::load_async]
+pub struct BankLoad {
+    state: Arc<Mutex<BankLoadState>>,
+}
+
+impl BankLoad {
+    fn ready(result: Result<Bank, AkResult>) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(BankLoadState {
+                result: Some(result),
+                waker: None,
+                abandoned: false,
+            })),
+        }
+    }
+
+    /// A pending load, with the callback that completes it.
+    fn pending(
+        name: Option<String>,
+        memory_view: Option<BankMemory>,
+    ) -> (Self, impl FnOnce(BankCallbackResult) + Send + 'static) {
+        let state = Arc::new(Mutex::new(BankLoadState {
+            result: None,
+            waker: None,
+            abandoned: false,
+        }));
+
+        let on_loaded_state = state.clone();
+        let on_loaded = move |result: BankCallbackResult| {
+            let result = result.map(|id| register(id, name.as_deref(), memory_view));
+            let mut state = on_loaded_state.lock().unwrap();
+            if state.abandoned {
+                // Unloading synchronously from the Bank Manager thread would deadlock
+                std::thread::spawn(move || drop(result));
+                return;
+            }
+            state.result = Some(result);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        };
+
+        (Self { state }, on_loaded)
+    }
+
+    /// Whether the load has completed, successfully or not.
+    pub fn is_done(&self) -> bool {
+        self.state.lock().unwrap().result.is_some()
+    }
+
+    /// Get the result of the load if it has completed, without blocking.
+    pub fn try_take(&mut self) -> Option<Result<Bank, AkResult>> {
+        self.state.lock().unwrap().result.take()
+    }
+}
+
+impl Future for BankLoad {
+    type Output = Result<Bank, AkResult>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for BankLoad {
+    fn drop(&mut self) {
+        let result = {
+            let mut state = self.state.lock().unwrap();
+            state.abandoned = true;
+            state.result.take()
+        };
+        drop(result);
+    }
+}
+
+/// Forget all the banks loaded through [Bank] handles, after they have all been unloaded.
+pub(crate) fn forget_all() {
+    BANKS.lock().unwrap().take();
+    BANKS_CHANGED.notify_all();
+}
+
+/// Whether a bank is loaded through [Bank] handles.
+pub(crate) fn is_loaded(id: AkBankID) -> bool {
+    with_entry(id, |entry| entry.state == BankState::Loaded).unwrap_or(false)
+}
+
+/// The ID and name of the banks loaded by name through [Bank] handles.
+pub(crate) fn named_banks() -> Vec<(AkBankID, String)> {
+    BANKS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or_else(Vec::new, |banks| {
+            banks
+                .iter()
+                .filter_map(|(id, entry)| entry.name.clone().map(|name| (*id, name)))
+                .collect()
+        })
+}
+
+/// Unload and load again a bank loaded by name through [Bank] handles, which stay valid.
+///
+/// If the bank can't be loaded again, the handles stay valid but the bank is not loaded anymore,
+/// until it is reloaded successfully.
+///
+/// *Return* [AK_IDNotFound](AkResult::AK_IDNotFound) if no [Bank] handle loaded `id` by name, or
+/// the error of [unload_bank_by_id] or [load_bank_by_name].
+pub(crate) fn reload(id: AkBankID) -> Result<(), AkResult> {
+    let (name, loads) = {
+        let mut banks = wait_settled(BANKS.lock().unwrap(), id);
+        let entry = banks
+            .as_mut()
+            .and_then(|banks| banks.get_mut(&id))
+            .ok_or(AkResult::AK_IDNotFound)?;
+        let name = entry.name.clone().ok_or(AkResult::AK_IDNotFound)?;
+        entry.state = BankState::Loading;
+        (name, std::mem::take(&mut entry.loads))
+    };
+
+    let unloaded = unload_all(id, loads);
+    let loaded = load_bank_by_name(&name);
+
+    let orphans = {
+        let mut banks = BANKS.lock().unwrap();
+        let banks = banks.get_or_insert_with(HashMap::new);
+        if loaded.is_ok() {
+            banks
+                .entry(id)
+                .or_insert_with(|| BankEntry::new(BankState::Loading))
+                .loads
+                .push(None);
+        }
+        settle(banks, id)
+    };
+    BANKS_CHANGED.notify_all();
+    unload_orphans(id, orphans);
+
+    unloaded.and(loaded.map(|_| ()))
+}
+
+/// The ID the sound engine gives to the bank `name`, which may have the BNK extension.
+pub(crate) fn bank_id_from_name(name: &str) -> AkBankID {
+    get_id_from_string(name.strip_suffix(".bnk").unwrap_or(name))
+}
+
+/// Register a new reference to a bank, calling `load` if it isn't loaded yet.
+///
+/// `id` is the expected ID of the bank, when known before loading it. `load` is called without
+/// holding the registry lock, with `memory_view`.
+fn acquire<F>(
+    id: Option<AkBankID>,
+    name: Option<&str>,
+    memory_view: Option<BankMemory>,
+    load: F,
+) -> Result<Bank, AkResult>
+where
+    F: FnOnce(Option<&BankMemory>) -> Result<AkBankID, AkResult>,
+{
+    if let Some(id) = id {
+        let mut banks = wait_settled(BANKS.lock().unwrap(), id);
+        let entry = banks
+            .get_or_insert_with(HashMap::new)
+            .entry(id)
+            .or_insert_with(|| BankEntry::new(BankState::Unloaded));
+        if entry.state == BankState::Loaded {
+            entry.ref_count += 1;
+            return Ok(Bank { id });
+        }
+        entry.state = BankState::Loading;
+    }
+
+    let loaded = load(memory_view.as_ref());
+
+    let (result, orphans) = {
+        let mut banks = BANKS.lock().unwrap();
+        let banks = banks.get_or_insert_with(HashMap::new);
+        let result = loaded.map(|loaded_id| register_in(banks, loaded_id, name, memory_view));
+        let orphans = id.map_or_else(Vec::new, |id| settle(banks, id));
+        (result, orphans)
+    };
+    BANKS_CHANGED.notify_all();
+    if let Some(id) = id {
+        unload_orphans(id, orphans);
+    }
+    result
+}
+
+/// A new reference to a bank, if it is already loaded.
+fn try_share(id: AkBankID) -> Option<Bank> {
+    with_entry(id, |entry| {
+        let loaded = entry.state == BankState::Loaded;
+        if loaded {
+            entry.ref_count += 1;
+        }
+        loaded
+    })
+    .filter(|loaded| *loaded)
+    .map(|_| Bank { id })
+}
+
+/// Register a new reference to a bank that has just been loaded.
+fn register(id: AkBankID, name: Option<&str>, memory_view: Option<BankMemory>) -> Bank {
+    let bank = {
+        let mut banks = BANKS.lock().unwrap();
+        register_in(
+            banks.get_or_insert_with(HashMap::new),
+            id,
+            name,
+            memory_view,
+        )
+    };
+    BANKS_CHANGED.notify_all();
+    bank
+}
+
+/// Register a new reference to a bank, along with the sound engine load it comes from.
+///
+/// A bank loading or unloading on another thread is settled by that thread once done.
+fn register_in(
+    banks: &mut HashMap<AkBankID, BankEntry>,
+    id: AkBankID,
+    name: Option<&str>,
+    memory_view: Option<BankMemory>,
+) -> Bank {
+    let entry = banks
+        .entry(id)
+        .or_insert_with(|| BankEntry::new(BankState::Loaded));
+    if entry.name.is_none() {
+        entry.name = name.map(str::to_string);
+    }
+    if entry.state == BankState::Unloaded {
+        entry.state = BankState::Loaded;
+    }
+    entry.ref_count += 1;
+    entry.loads.push(memory_view);
+    Bank { id }
+}
+
+/// Release a reference to a bank, unloading it if it was the last one.
+fn release(id: AkBankID) -> Result<(), AkResult> {
+    let loads = {
+        let mut banks = BANKS.lock().unwrap();
+        let banks = match banks.as_mut() {
+            Some(banks) => banks,
+            None => return Ok(()),
+        };
+        let entry = match banks.get_mut(&id) {
+            Some(entry) => entry,
+            None => return Ok(()),
+        };
+
+        entry.ref_count = entry.ref_count.saturating_sub(1);
+        if entry.ref_count > 0 {
+            return Ok(());
+        }
+        match entry.state {
+            // Settled by the thread loading or unloading it
+            BankState::Loading | BankState::Unloading => return Ok(()),
+            BankState::Unloaded => {
+                banks.remove(&id);
+                return Ok(());
+            }
+            BankState::Loaded => {
+                entry.state = BankState::Unloading;
+                std::mem::take(&mut entry.loads)
+            }
+        }
+    };
+
+    let result = unload_all(id, loads);
+
+    let orphans = {
+        let mut banks = BANKS.lock().unwrap();
+        banks
+            .as_mut()
+            .map_or_else(Vec::new, |banks| settle(banks, id))
+    };
+    BANKS_CHANGED.notify_all();
+    unload_orphans(id, orphans);
+    result
+}
+
+/// Wait until no other thread is loading or unloading the bank `id`.
+fn wait_settled(mut banks: MutexGuard<'static, Banks>, id: AkBankID) -> MutexGuard<'static, Banks> {
+    while banks
+        .as_ref()
+        .and_then(|banks| banks.get(&id))
+        .map_or(false, |entry| {
+            matches!(entry.state, BankState::Loading | BankState::Unloading)
+        })
+    {
+        banks = BANKS_CHANGED.wait(banks).unwrap();
+    }
+    banks
+}
+
+/// Settle the state of a bank once done loading or unloading it, forgetting it if no handle is
+/// left.
+///
+/// *Return* The loads of the bank if it was forgotten, to unload.
+fn settle(banks: &mut HashMap<AkBankID, BankEntry>, id: AkBankID) -> Vec<Option<BankMemory>> {
+    let entry = match banks.get_mut(&id) {
+        Some(entry) => entry,
+        None => return Vec::new(),
+    };
+    if entry.ref_count > 0 {
+        entry.state = if entry.loads.is_empty() {
+            BankState::Unloaded
+        } else {
+            BankState::Loaded
+        };
+        return Vec::new();
+    }
+    banks.remove(&id).map_or_else(Vec::new, |entry| entry.loads)
+}
+
+/// Unload a bank once for each of its sound engine loads, then drop the buffers it was loaded from.
+///
+/// The buffers of the loads that couldn't be unloaded are leaked, as the sound engine may still
+/// use them.
+fn unload_all(id: AkBankID, loads: Vec<Option<BankMemory>>) -> Result<(), AkResult> {
+    if !sound_engine::is_initialized() {
+        return Ok(());
+    }
+
+    let mut result = Ok(());
+    for memory_view in loads {
+        if let Err(akr) = unload_bank_by_id(id, memory_view.as_deref()) {
+            std::mem::forget(memory_view);
+            result = Err(akr);
+        }
+    }
+    result
+}
+
+/// Unload the loads of a bank that lost its last handle while it was loading.
+fn unload_orphans(id: AkBankID, loads: Vec<Option<BankMemory>>) {
+    if let Err(akr) = unload_all(id, loads) {
+        log::warn!("Couldn't unload bank {}: {}", id, akr);
+    }
+}
+
+fn with_entry<R, F: FnOnce(&mut BankEntry) -> R>(id: AkBankID, f: F) -> Option<R> {
+    BANKS
+        .lock()
+        .unwrap()
+        .as_mut()
+        .and_then(|banks| banks.get_mut(&id))
+        .map(f)
+}
@@ -0,0 +1,485 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Keep the memory used by SoundBanks under a byte budget.
+//!
+//! A [BankCache] loads banks by name, counting their size against its budget. When loading a bank
+//! would exceed the budget, the least recently used banks are unloaded first, skipping the banks
+//! pinned by [BankCache::post]: those with events posted through it that are still playing.
+//!
+//! *Warning* The cache only sees the events posted with [BankCache::post]. A bank whose events are
+//! posted any other way, such as with [PostEvent::post], is not pinned and can be evicted while
+//! they play, which stops them.
+
+use crate::bank::Bank;
+use crate::sound_engine::{PlayingHandle, PostEvent};
+use crate::{AkBankID, AkCallbackInfo, AkCallbackType, AkPlayingID, AkResult};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+struct CacheEntry {
+    bank: Bank,
+    name: String,
+    size: usize,
+    last_used: u64,
+}
+
+/// Banks loaded under a byte budget, with least recently used eviction.
+///
+/// *See also* [module documentation](crate::bank_cache)
+pub struct BankCache {
+    base_path: PathBuf,
+    budget: usize,
+    sizes: HashMap<String, usize>,
+    entries: Vec<CacheEntry>,
+    pinning: Arc<Mutex<PinningEvents>>,
+    clock: u64,
+}
+
+/// The events posted with [BankCache::post] that are still playing, pinning their bank.
+#[derive(Default)]
+struct PinningEvents {
+    by_bank: HashMap<AkBankID, HashSet<AkPlayingID>>,
+    /// Whether an event is being posted, and not registered in `by_bank` yet.
+    posting: bool,
+    /// Events that ended while being posted, before they could be registered.
+    ended_early: HashSet<AkPlayingID>,
+}
+
+impl BankCache {
+    /// A cache keeping the banks it loads under `budget` bytes.
+    ///
+    /// The size of a bank is the size of its file in `base_path`, unless it is given with
+    /// [with_size](Self::with_size).
+    pub fn new<P: AsRef<Path>>(base_path: P, budget: usize) -> Self {
+        Self {
+            base_path: base_path.as_ref().to_path_buf(),
+            budget,
+            sizes: HashMap::new(),
+            entries: Vec::new(),
+            pinning: Arc::new(Mutex::new(PinningEvents::default())),
+            clock: 0,
+        }
+    }
+
+    /// Count `size` bytes for the bank `name` instead of the size of its file, for instance when
+    /// banks are in a file package.
+    pub fn with_size<T: AsRef<str>>(mut self, name: T, size: usize) -> Self {
+        self.sizes
+            .insert(bank_name(name.as_ref()).to_string(), size);
+        self
+    }
+
+    /// The byte budget of the cache.
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    /// Change the byte budget of the cache, evicting banks if needed.
+    ///
+    /// *Return* Whether the banks in the cache fit in the new budget; they might not if too many
+    /// are pinned by [post](Self::post), in which case none is evicted.
+    pub fn set_budget(&mut self, budget: usize) -> bool {
+        self.budget = budget;
+        self.make_room(0)
+    }
+
+    /// Number of bytes counted for the banks in the cache.
+    pub fn used(&self) -> usize {
+        self.entries.iter().map(|entry| entry.size).sum()
+    }
+
+    /// Whether the bank `name` is in the cache.
+    pub fn contains<T: AsRef<str>>(&self, name: T) -> bool {
+        self.entry(name.as_ref()).is_some()
+    }
+
+    /// Load the bank `name` synchronously if it is not in the cache yet, evicting the least
+    /// recently used banks that are not pinned by [post](Self::post) to make room for it.
+    ///
+    /// *Warning* Only the events posted with [post](Self::post) pin their bank: a bank whose events
+    /// were posted with [PostEvent::post] or [PostEvent::post_with_callback] can be evicted, and its
+    /// events stopped, while they play.
+    ///
+    /// Banks are only evicted once the bank is loaded: if it fails to load, the cache is left
+    /// unchanged.
+    ///
+    /// *Return*
+    /// > - A handle to the bank, which is kept loaded by the cache until it is evicted
+    /// > - [AK_InsufficientMemory](AkResult::AK_InsufficientMemory) if not enough banks could be
+    /// evicted to fit the bank in the budget
+    /// > - The errors of [Bank::load] otherwise
+    pub fn load<T: AsRef<str>>(&mut self, name: T) -> Result<Bank, AkResult> {
+        let name = bank_name(name.as_ref());
+        let now = self.tick();
+        if let Some(entry) = self.entries.iter_mut().find(|entry| entry.name == name) {
+            entry.last_used = now;
+            return Ok(entry.bank.clone());
+        }
+
+        let size = self.size_of(name);
+        let evictions = match plan_evictions(&self.candidates(), size, self.budget) {
+            Some(evictions) => evictions,
+            None => {
+                log::warn!(
+                    "Bank {} ({} bytes) doesn't fit in the budget of {} bytes",
+                    name,
+                    size,
+                    self.budget
+                );
+                return Err(AkResult::AK_InsufficientMemory);
+            }
+        };
+
+        let (bank, evicted) = load_then_evict(&mut self.entries, evictions, || Bank::load(name))?;
+        for entry in evicted {
+            self.unload(entry);
+        }
+        self.entries.push(CacheEntry {
+            bank: bank.clone(),
+            name: name.to_string(),
+            size,
+            last_used: now,
+        });
+        Ok(bank)
+    }
+
+    /// Load the bank `name` with [load](Self::load), then post `event`.
+    ///
+    /// The event pins the bank: it is not evicted to make room for other banks until the event
+    /// ends.
+    ///
+    /// *Return* The [PlayingHandle] of the event, or the errors of [load](Self::load) and
+    /// [PostEvent::post].
+    pub fn post<T: AsRef<str>>(
+        &mut self,
+        name: T,
+        event: &PostEvent,
+    ) -> Result<PlayingHandle, AkResult> {
+        let bank_id = self.load(name)?.id();
+
+        // The event can end before it is registered: the callback then records it in
+        // `ended_early` instead.
+        self.pinning.lock().unwrap().posting = true;
+        let pinning = self.pinning.clone();
+        let posted = event.post_with_callback(move |info| {
+            if let AkCallbackInfo::Event {
+                callback_type,
+                playing_id,
+                ..
+            } = info
+            {
+                if callback_type.contains(AkCallbackType::AK_EndOfEvent) {
+                    pinning.lock().unwrap().end(bank_id, playing_id);
+                }
+            }
+        });
+
+        let mut pinning = self.pinning.lock().unwrap();
+        pinning.posting = false;
+        let ended_early = std::mem::take(&mut pinning.ended_early);
+        let handle = posted?;
+        if !ended_early.contains(&handle.playing_id()) {
+            pinning
+                .by_bank
+                .entry(bank_id)
+                .or_default()
+                .insert(handle.playing_id());
+        }
+
+        Ok(handle)
+    }
+
+    /// Remove the bank `name` from the cache, unloading it if no other [Bank] handle uses it.
+    ///
+    /// The bank is removed even if it is pinned by [post](Self::post), and regardless of the events
+    /// posted on it any other way: their sounds stop if the bank is unloaded.
+    ///
+    /// *Return* Whether the bank was in the cache.
+    pub fn evict<T: AsRef<str>>(&mut self, name: T) -> bool {
+        let name = bank_name(name.as_ref());
+        match self.entries.iter().position(|entry| entry.name == name) {
+            Some(index) => {
+                self.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove all the banks from the cache.
+    pub fn clear(&mut self) {
+        while !self.entries.is_empty() {
+            self.remove(self.entries.len() - 1);
+        }
+    }
+
+    fn entry(&self, name: &str) -> Option<&CacheEntry> {
+        let name = bank_name(name);
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn size_of(&self, name: &str) -> usize {
+        if let Some(size) = self.sizes.get(name) {
+            return *size;
+        }
+
+        let path = self.base_path.join(format!("{}.bnk", name));
+        match path.metadata() {
+            Ok(metadata) => metadata.len() as usize,
+            Err(e) => {
+                log::warn!(
+                    "Couldn't get the size of bank {} ({}), counting 0 bytes: {}",
+                    name,
+                    path.display(),
+                    e
+                );
+                0
+            }
+        }
+    }
+
+    /// Evict the least recently used banks that are not pinned until `size` more bytes fit in
+    /// the budget.
+    ///
+    /// *Return* Whether they fit; nothing is evicted if they can't.
+    fn make_room(&mut self, size: usize) -> bool {
+        match plan_evictions(&self.candidates(), size, self.budget) {
+            Some(mut evictions) => {
+                evictions.sort_unstable_by(|a, b| b.cmp(a));
+                for index in evictions {
+                    self.remove(index);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The banks of the cache, as seen by [plan_evictions].
+    fn candidates(&self) -> Vec<Candidate> {
+        let pinning = self.pinning.lock().unwrap();
+        self.entries
+            .iter()
+            .map(|entry| Candidate {
+                size: entry.size,
+                last_used: entry.last_used,
+                pinned: pinning.by_bank.contains_key(&entry.bank.id()),
+            })
+            .collect()
+    }
+
+    fn remove(&mut self, index: usize) {
+        let entry = self.entries.remove(index);
+        self.unload(entry);
+    }
+
+    fn unload(&self, entry: CacheEntry) {
+        self.pinning
+            .lock()
+            .unwrap()
+            .by_bank
+            .remove(&entry.bank.id());
+        log::debug!("Evicting bank {} ({} bytes)", entry.name, entry.size);
+        if let Err(akr) = entry.bank.unload() {
+            log::warn!("Couldn't unload bank {}: {}", entry.name, akr);
+        }
+    }
+}
+
+impl PinningEvents {
+    fn end(&mut self, bank_id: AkBankID, playing_id: AkPlayingID) {
+        let registered = match self.by_bank.get_mut(&bank_id) {
+            Some(playing_ids) => {
+                let registered = playing_ids.remove(&playing_id);
+                if playing_ids.is_empty() {
+                    self.by_bank.remove(&bank_id);
+                }
+                registered
+            }
+            None => false,
+        };
+        if !registered && self.posting {
+            self.ended_early.insert(playing_id);
+        }
+    }
+}
+
+/// A bank of the cache, as seen by [plan_evictions].
+#[derive(Debug, Copy, Clone)]
+struct Candidate {
+    size: usize,
+    last_used: u64,
+    pinned: bool,
+}
+
+/// The indices of the banks to evict so that `size` more bytes fit in `budget`, least recently
+/// used first, skipping the pinned banks.
+///
+/// *Return* [None] if the pinned banks alone leave less than `size` bytes in the budget.
+fn plan_evictions(candidates: &[Candidate], size: usize, budget: usize) -> Option<Vec<usize>> {
+    let pinned: usize = candidates
+        .iter()
+        .filter(|candidate| candidate.pinned)
+        .map(|candidate| candidate.size)
+        .sum();
+    if pinned
+        .checked_add(size)
+        .map_or(true, |needed| needed > budget)
+    {
+        return None;
+    }
+
+    let mut lru: Vec<_> = (0..candidates.len())
+        .filter(|&index| !candidates[index].pinned)
+        .collect();
+    lru.sort_by_key(|&index| candidates[index].last_used);
+
+    let mut used: usize = candidates.iter().map(|candidate| candidate.size).sum();
+    let mut evictions = Vec::new();
+    for index in lru {
+        if used + size <= budget {
+            break;
+        }
+        used -= candidates[index].size;
+        evictions.push(index);
+    }
+    Some(evictions)
+}
+
+/// Call `load`, then remove the `evictions` planned by [plan_evictions] from `entries` if it
+/// succeeded; `entries` are left unchanged if it failed.
+///
+/// *Return* The result of `load` and the evicted entries, or the error of `load`.
+fn load_then_evict<E, T, F>(
+    entries: &mut Vec<E>,
+    mut evictions: Vec<usize>,
+    load: F,
+) -> Result<(T, Vec<E>), AkResult>
+where
+    F: FnOnce() -> Result<T, AkResult>,
+{
+    let loaded = load()?;
+    evictions.sort_unstable_by(|a, b| b.cmp(a));
+    let evicted = evictions
+        .into_iter()
+        .map(|index| entries.remove(index))
+        .collect();
+    Ok((loaded, evicted))
+}
+
+fn bank_name(name: &str) -> &str {
+    name.strip_suffix(".bnk").unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_then_evict, plan_evictions, Candidate, PinningEvents};
+    use crate::AkResult;
+
+    fn candidate(size: usize, last_used: u64, pinned: bool) -> Candidate {
+        Candidate {
+            size,
+            last_used,
+            pinned,
+        }
+    }
+
+    #[test]
+    fn nothing_evicted_when_it_fits() {
+        let candidates = [candidate(10, 1, false), candidate(20, 2, false)];
+        assert_eq!(plan_evictions(&candidates, 70, 100), Some(vec![]));
+        assert_eq!(plan_evictions(&[], 100, 100), Some(vec![]));
+    }
+
+    #[test]
+    fn least_recently_used_evicted_first() {
+        let candidates = [
+            candidate(30, 3, false),
+            candidate(30, 1, false),
+            candidate(30, 2, false),
+        ];
+        assert_eq!(plan_evictions(&candidates, 20, 100), Some(vec![1]));
+        assert_eq!(plan_evictions(&candidates, 50, 100), Some(vec![1, 2]));
+        assert_eq!(plan_evictions(&candidates, 100, 100), Some(vec![1, 2, 0]));
+    }
+
+    #[test]
+    fn pinned_banks_never_evicted() {
+        let candidates = [
+            candidate(30, 1, true),
+            candidate(30, 2, false),
+            candidate(30, 3, false),
+        ];
+        assert_eq!(plan_evictions(&candidates, 40, 100), Some(vec![1]));
+        assert_eq!(plan_evictions(&candidates, 70, 100), Some(vec![1, 2]));
+    }
+
+    #[test]
+    fn nothing_evicted_when_pinned_banks_leave_no_room() {
+        let candidates = [
+            candidate(30, 1, false),
+            candidate(60, 2, true),
+            candidate(10, 3, false),
+        ];
+        assert_eq!(plan_evictions(&candidates, 41, 100), None);
+        assert_eq!(plan_evictions(&candidates, usize::MAX, 100), None);
+        assert_eq!(plan_evictions(&candidates, 40, 100), Some(vec![0, 2]));
+    }
+
+    #[test]
+    fn failed_load_evicts_nothing() {
+        let candidates = [
+            candidate(30, 2, false),
+            candidate(30, 1, false),
+            candidate(30, 3, false),
+        ];
+        let evictions = plan_evictions(&candidates, 50, 100).unwrap();
+        assert_eq!(evictions, vec![1, 2]);
+
+        let mut entries = vec!["a", "b", "c"];
+        let result = load_then_evict(&mut entries, evictions.clone(), || {
+            Err::<&str, _>(AkResult::AK_FileNotFound)
+        });
+        assert_eq!(result, Err(AkResult::AK_FileNotFound));
+        assert_eq!(entries, vec!["a", "b", "c"]);
+
+        let result = load_then_evict(&mut entries, evictions, || Ok("d"));
+        assert_eq!(result, Ok(("d", vec!["c", "b"])));
+        assert_eq!(entries, vec!["a"]);
+    }
+
+    #[test]
+    fn ended_events_pruned() {
+        let mut pinning = PinningEvents::default();
+        pinning.by_bank.entry(1).or_default().extend([10, 11]);
+        pinning.end(1, 10);
+        assert!(pinning.by_bank.contains_key(&1));
+        pinning.end(1, 11);
+        assert!(pinning.by_bank.is_empty());
+        assert!(pinning.ended_early.is_empty());
+    }
+
+    #[test]
+    fn events_ending_while_posted_recorded() {
+        let mut pinning = PinningEvents {
+            posting: true,
+            ..Default::default()
+        };
+        pinning.end(1, 10);
+        assert!(pinning.ended_early.contains(&10));
+        assert!(pinning.by_bank.is_empty());
+
+        pinning.posting = false;
+        pinning.ended_early.clear();
+        pinning.end(1, 11);
+        assert!(pinning.ended_early.is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Know which SoundBanks contain which events, to load them before posting.
+//!
+//! A [BankIndex] is typically built from the `SoundbanksInfo.json` file Wwise generates next to
+//! the banks (requires the `serde_json` feature; enable *Generate JSON Metadata* in the SoundBanks
+//! settings of the Wwise project). It can then be given to
+//! [PostEvent::with_bank_index](crate::sound_engine::PostEvent::with_bank_index) so that the bank
+//! of the event is loaded before it is posted.
+//!
+//! The `SoundbanksInfo.xml` file Wwise generates by default is not parsed: generate the JSON
+//! metadata instead, or fill the index from the XML file with [BankIndex::insert].
+//!
+//! The banks loaded by the index stay loaded as long as the index lives, or until
+//! [BankIndex::release_banks] is called.
+
+use crate::bank::Bank;
+use crate::sound_engine;
+use crate::{AkBankID, AkID, AkResult, AkUniqueID};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A SoundBank listed in a [BankIndex].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BankInfo {
+    /// ID of the bank.
+    pub id: AkBankID,
+    /// Name of the bank, without extension, as given to [Bank::load].
+    pub name: String,
+}
+
+/// Maps events to the SoundBanks that contain them.
+///
+/// *See also* [module documentation](crate::bank_index)
+#[derive(Debug, Default)]
+pub struct BankIndex {
+    banks: Vec<BankInfo>,
+    events: HashMap<AkUniqueID, Vec<usize>>,
+    event_names: HashMap<String, AkUniqueID>,
+    loaded: Mutex<HashMap<AkBankID, Bank>>,
+}
+
+/// Error while reading a [BankIndex].
+#[derive(Debug)]
+pub enum BankIndexError {
+    /// The file could not be read.
+    Io(std::io::Error),
+    /// The file is not valid JSON.
+    #[cfg(feature = "serde_json")]
+    Json(serde_json::Error),
+    /// The file is valid JSON, but not a SoundBanks info file.
+    Malformed(&'static str),
+}
+
+impl std::fmt::Display for BankIndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "couldn't read the SoundBanks info file: {}", e),
+            #[cfg(feature = "serde_json")]
+            Self::Json(e) => write!(f, "invalid SoundBanks info file: {}", e),
+            Self::Malformed(what) => write!(f, "malformed SoundBanks info file: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for BankIndexError {}
+
+impl BankIndex {
+    /// An empty index, to fill with [insert](Self::insert).
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Read the index from a `SoundbanksInfo.json` file.
+    #[cfg(feature = "serde_json")]
+    pub fn from_file<P: AsRef<std::path::Path>>(path: P) -> Result<Self, BankIndexError> {
+        let json = std::fs::read_to_string(path).map_err(BankIndexError::Io)?;
+        Self::from_json(&json)
+    }
+
+    /// Read the index from the content of a `SoundbanksInfo.json` file.
+    ///
+    /// The Init bank is left out, as it must be loaded before any other bank anyway.
+    #[cfg(feature = "serde_json")]
+    pub fn from_json(json: &str) -> Result<Self, BankIndexError> {
+        use serde_json::Value;
+
+        fn parse_id(value: &Value) -> Option<AkUniqueID> {
+            match value {
+                Value::String(id) => id.parse().ok(),
+                Value::Number(id) => id.as_u64().and_then(|id| id.try_into().ok()),
+                _ => None,
+            }
+        }
+
+        let info: Value = serde_json::from_str(json).map_err(BankIndexError::Json)?;
+        let banks =
+            info["SoundBanksInfo"]["SoundBanks"]
+                .as_array()
+                .ok_or(BankIndexError::Malformed(
+                    "no SoundBanksInfo.SoundBanks array",
+                ))?;
+
+        let mut index = Self::new();
+        for bank in banks {
+            let id = parse_id(&bank["Id"]).ok_or(BankIndexError::Malformed("bank without Id"))?;
+            let name = bank["ShortName"]
+                .as_str()
+                .ok_or(BankIndexError::Malformed("bank without ShortName"))?;
+            if name.eq_ignore_ascii_case("Init") {
+                continue;
+            }
+
+            for event in bank["IncludedEvents"].as_array().into_iter().flatten() {
+                let event_id =
+                    parse_id(&event["Id"]).ok_or(BankIndexError::Malformed("event without Id"))?;
+                let event_name = event["Name"]
+                    .as_str()
+                    .ok_or(BankIndexError::Malformed("event without Name"))?;
+                index.insert(event_id, event_name, id, name);
+            }
+        }
+
+        Ok(index)
+    }
+
+    /// Record that the event `event_id` named `event_name` is in the bank `bank_id` named
+    /// `bank_name`.
+    pub fn insert(
+        &mut self,
+        event_id: AkUniqueID,
+        event_name: &str,
+        bank_id: AkBankID,
+        bank_name: &str,
+    ) {
+        let bank = match self.banks.iter().position(|bank| bank.id == bank_id) {
+            Some(bank) => bank,
+            None => {
+                self.banks.push(BankInfo {
+                    id: bank_id,
+                    name: bank_name.to_string(),
+                });
+                self.banks.len() - 1
+            }
+        };
+
+        let banks = self.events.entry(event_id).or_default();
+        if !banks.contains(&bank) {
+            banks.push(bank);
+        }
+        self.event_names.insert(event_name.to_lowercase(), event_id);
+    }
+
+    /// The banks containing `event`.
+    ///
+    /// Event names are case-insensitive, like in Wwise.
+    pub fn banks_for<'a, T: Into<AkID<'a>>>(&self, event: T) -> Vec<&BankInfo> {
+        let event_id = match event.into() {
+            AkID::Name(name) => self.event_names.get(&name.to_lowercase()).copied(),
+            AkID::ID(id) => Some(id),
+        };
+        event_id
+            .and_then(|id| self.events.get(&id))
+            .map_or_else(Vec::new, |banks| {
+                banks.iter().map(|&bank| &self.banks[bank]).collect()
+            })
+    }
+
+    /// Load synchronously a bank containing `event`, unless one is already loaded.
+    ///
+    /// Nothing is loaded if `event` is not in the index.
+    ///
+    /// *Return* See [Bank::load].
+    pub fn load_banks_for<'a, T: Into<AkID<'a>>>(&self, event: T) -> Result<(), AkResult> {
+        if let Some(bank) = self.bank_to_load(event.into()) {
+            log::debug!("Loading bank {} for event", bank.name);
+            self.keep(Bank::load(bank.name.as_str())?);
+        }
+        Ok(())
+    }
+
+    /// Load asynchronously a bank containing `event`, unless one is already loaded.
+    ///
+    /// Nothing is loaded if `event` is not in the index.
+    ///
+    /// *Return* See [Bank::load_async].
+    pub async fn load_banks_for_async<'a, T: Into<AkID<'a>>>(
+        &self,
+        event: T,
+    ) -> Result<(), AkResult> {
+        if let Some(bank) = self.bank_to_load(event.into()) {
+            log::debug!("Loading bank {} for event", bank.name);
+            self.keep(Bank::load_async(bank.name.as_str()).await?);
+        }
+        Ok(())
+    }
+
+    /// Drop the handles of the banks loaded by this index, unloading the ones no other [Bank]
+    /// handle uses.
+    pub fn release_banks(&self) {
+        let loaded = std::mem::take(&mut *self.loaded.lock().unwrap());
+        drop(loaded);
+    }
+
+    /// The bank to load to be able to post `event`, if none of the banks containing it is loaded,
+    /// whether through a [Bank] handle or directly with the [sound_engine] functions.
+    fn bank_to_load(&self, event: AkID) -> Option<&BankInfo> {
+        let banks = self.banks_for(event);
+        if banks
+            .iter()
+            .any(|bank| sound_engine::is_bank_loaded(bank.id))
+        {
+            None
+        } else {
+            banks.first().copied()
+        }
+    }
+
+    fn keep(&self, bank: Bank) {
+        self.loaded.lock().unwrap().insert(bank.id(), bank);
+    }
+}
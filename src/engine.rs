@@ -0,0 +1,382 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Initialization and termination of all the Wwise modules, in the right order.
+//!
+//! Wwise modules must be initialized in this order, and terminated in the reverse order:
+//! 1. [memory_mgr](crate::memory_mgr)
+//! 2. [stream_mgr](crate::stream_mgr)
+//! 3. [sound_engine](crate::sound_engine)
+//! 4. [music_engine](crate::music_engine) (optional)
+//! 5. [spatial_audio](crate::spatial_audio) (optional)
+//! 6. [communication](crate::communication) (optional, not available with `--cfg wwrelease`)
+//!
+//! [EngineBuilder::init] does exactly that and returns an [Engine] which terminates everything
+//! when it is dropped, including when unwinding from a panic.
+
+#[cfg(not(wwrelease))]
+use crate::communication;
+use crate::low_level_io::LowLevelIo;
+use crate::settings::*;
+use crate::{memory_mgr, music_engine, sound_engine, spatial_audio, stream_mgr, AkResult};
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static ENGINE_ALIVE: AtomicBool = AtomicBool::new(false);
+
+/// A step of the initialization of an [Engine].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EngineStep {
+    /// Another [Engine] is already alive.
+    Engine,
+    /// See [memory_mgr::init].
+    MemoryMgr,
+    /// See [stream_mgr::init_default_stream_mgr] and [stream_mgr::init_custom_stream_mgr].
+    StreamMgr,
+    /// See [stream_mgr::set_current_language].
+    Language,
+    /// See [sound_engine::init].
+    SoundEngine,
+    /// See [music_engine::init].
+    MusicEngine,
+    /// See [spatial_audio::init].
+    SpatialAudio,
+    /// See [communication::init](crate::communication::init).
+    Communication,
+}
+
+impl Display for EngineStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            EngineStep::Engine => "engine",
+            EngineStep::MemoryMgr => "memory manager",
+            EngineStep::StreamMgr => "stream manager",
+            EngineStep::Language => "language",
+            EngineStep::SoundEngine => "sound engine",
+            EngineStep::MusicEngine => "music engine",
+            EngineStep::SpatialAudio => "spatial audio",
+            EngineStep::Communication => "communication",
+        })
+    }
+}
+
+/// Error returned by [EngineBuilder::init]: the step that failed and why.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct EngineError {
+    /// The step that failed.
+    pub step: EngineStep,
+    /// What the failing step returned.
+    pub result: AkResult,
+}
+
+impl std::error::Error for EngineError {}
+
+impl Display for EngineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} initialization failed: {}", self.step, self.result)
+    }
+}
+
+impl From<EngineError> for AkResult {
+    fn from(error: EngineError) -> Self {
+        error.result
+    }
+}
+
+/// Helper to initialize all the Wwise modules in the right order.
+///
+/// Use [EngineBuilder::init] to initialize the modules; every setting defaults to the value of its
+/// `Default` implementation.
+///
+/// The music engine and spatial audio are only initialized if their settings were given with
+/// [with_music](EngineBuilder::with_music) and [with_spatial_audio](EngineBuilder::with_spatial_audio).
+/// The communication module is initialized by default (except with `--cfg wwrelease`).
+///
+/// *See also* [Engine]
+pub struct EngineBuilder {
+    mem_settings: AkMemSettings,
+    stream_mgr_settings: AkStreamMgrSettings,
+    device_settings: AkDeviceSettings,
+    bank_location: String,
+    base_paths: Vec<String>,
+    low_level_io: Option<Box<dyn LowLevelIo>>,
+    language: Option<String>,
+    init_settings: AkInitSettings,
+    platform_init_settings: AkPlatformInitSettings,
+    music_settings: Option<AkMusicSettings>,
+    spatial_audio_settings: Option<AkSpatialAudioInitSettings>,
+    #[cfg(not(wwrelease))]
+    comm_settings: Option<AkCommSettings>,
+}
+
+impl EngineBuilder {
+    /// Prepare the initialization of the engine, looking for soundbanks in `bank_location`.
+    ///
+    /// *See also* [stream_mgr::init_default_stream_mgr]
+    pub fn new<T: AsRef<str>>(bank_location: T) -> Self {
+        Self {
+            mem_settings: AkMemSettings::default(),
+            stream_mgr_settings: AkStreamMgrSettings::default(),
+            device_settings: AkDeviceSettings::default(),
+            bank_location: bank_location.as_ref().to_string(),
+            base_paths: Vec::new(),
+            low_level_io: None,
+            language: None,
+            init_settings: AkInitSettings::default(),
+            platform_init_settings: AkPlatformInitSettings::default(),
+            music_settings: None,
+            spatial_audio_settings: None,
+            #[cfg(not(wwrelease))]
+            comm_settings: Some(AkCommSettings::default()),
+        }
+    }
+
+    /// Default: [AkMemSettings::default]
+    pub fn with_mem_settings(mut self, settings: AkMemSettings) -> Self {
+        self.mem_settings = settings;
+        self
+    }
+
+    /// Default: [AkStreamMgrSettings::default]
+    pub fn with_stream_mgr_settings(mut self, settings: AkStreamMgrSettings) -> Self {
+        self.stream_mgr_settings = settings;
+        self
+    }
+
+    /// Default: [AkDeviceSettings::default]
+    ///
+    /// *Remark* The stream cache is always enabled, see [stream_mgr::init_default_stream_mgr].
+    pub fn with_device_settings(mut self, settings: AkDeviceSettings) -> Self {
+        self.device_settings = settings;
+        self
+    }
+
+    /// Also look for files in `path`, before the bank location and the paths added before it.
+    ///
+    /// *Remark* Base paths are only searched by the default streaming manager: they can't be
+    /// combined with [with_low_level_io](EngineBuilder::with_low_level_io), which makes
+    /// [init](EngineBuilder::init) fail.
+    ///
+    /// *See also* [stream_mgr::add_base_path]
+    pub fn with_base_path<T: AsRef<str>>(mut self, path: T) -> Self {
+        self.base_paths.push(path.as_ref().to_string());
+        self
+    }
+
+    /// Read files through `io` instead of from the bank location given to
+    /// [new](EngineBuilder::new).
+    ///
+    /// `io` serves all the files itself: [with_base_path](EngineBuilder::with_base_path) can't be
+    /// used along with it.
+    ///
+    /// *See also* [stream_mgr::init_custom_stream_mgr]
+    pub fn with_low_level_io<IO: LowLevelIo>(mut self, io: IO) -> Self {
+        self.low_level_io = Some(Box::new(io));
+        self
+    }
+
+    /// Language set with [stream_mgr::set_current_language] right after the stream manager is
+    /// initialized.
+    ///
+    /// Default: none
+    pub fn with_language<T: AsRef<str>>(mut self, language: T) -> Self {
+        self.language = Some(language.as_ref().to_string());
+        self
+    }
+
+    /// Default: [AkInitSettings::default]
+    pub fn with_init_settings(mut self, settings: AkInitSettings) -> Self {
+        self.init_settings = settings;
+        self
+    }
+
+    /// Default: [AkPlatformInitSettings::default]
+    pub fn with_platform_init_settings(mut self, settings: AkPlatformInitSettings) -> Self {
+        self.platform_init_settings = settings;
+        self
+    }
+
+    /// Also initialize the music engine with these settings.
+    pub fn with_music(mut self, settings: AkMusicSettings) -> Self {
+        self.music_settings = Some(settings);
+        self
+    }
+
+    /// Also initialize spatial audio with these settings.
+    pub fn with_spatial_audio(mut self, settings: AkSpatialAudioInitSettings) -> Self {
+        self.spatial_audio_settings = Some(settings);
+        self
+    }
+
+    /// Initialize the communication module with these settings instead of the default ones.
+    #[cfg(not(wwrelease))]
+    pub fn with_comms(mut self, settings: AkCommSettings) -> Self {
+        self.comm_settings = Some(settings);
+        self
+    }
+
+    /// Don't initialize the communication module.
+    #[cfg(not(wwrelease))]
+    pub fn without_comms(mut self) -> Self {
+        self.comm_settings = None;
+        self
+    }
+
+    /// Initialize all the modules, in order.
+    ///
+    /// If a step fails, the modules initialized so far are terminated in reverse order before
+    /// returning, so that [init](EngineBuilder::init) can be attempted again.
+    ///
+    /// *Return*
+    /// > - The [Engine] that will terminate all the modules when dropped, if successful
+    /// > - An [EngineError] with [EngineStep::StreamMgr] and
+    /// [AK_InvalidParameter](AkResult::AK_InvalidParameter) if base paths were added along with a
+    /// [LowLevelIo], before anything is initialized
+    /// > - An [EngineError] with [EngineStep::Engine] and [AK_Fail](AkResult::AK_Fail) if another [Engine] is alive
+    /// > - An [EngineError] with the step that failed and its result otherwise
+    pub fn init(mut self) -> Result<Engine, EngineError> {
+        if self.low_level_io.is_some() && !self.base_paths.is_empty() {
+            log::error!("Base paths can't be used along with a custom Low-Level I/O");
+            return Err(EngineError {
+                step: EngineStep::StreamMgr,
+                result: AkResult::AK_InvalidParameter,
+            });
+        }
+
+        if ENGINE_ALIVE
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(EngineError {
+                step: EngineStep::Engine,
+                result: AkResult::AK_Fail,
+            });
+        }
+
+        // From here on, dropping `engine` terminates whatever was initialized so far.
+        let mut engine = Engine {
+            memory_mgr: false,
+            stream_mgr: false,
+            custom_stream_mgr: false,
+            sound_engine: false,
+            music_engine: false,
+            #[cfg(not(wwrelease))]
+            communication: false,
+        };
+        memory_mgr::init(&mut self.mem_settings).map_err(failed(EngineStep::MemoryMgr))?;
+        engine.memory_mgr = true;
+
+        match self.low_level_io.take() {
+            Some(io) => {
+                stream_mgr::init_boxed_stream_mgr(
+                    &self.stream_mgr_settings,
+                    &mut self.device_settings,
+                    io,
+                )
+                .map_err(failed(EngineStep::StreamMgr))?;
+                engine.custom_stream_mgr = true;
+            }
+            None => stream_mgr::init_default_stream_mgr(
+                &self.stream_mgr_settings,
+                &mut self.device_settings,
+                &self.bank_location,
+            )
+            .map_err(failed(EngineStep::StreamMgr))?,
+        }
+        engine.stream_mgr = true;
+
+        for path in &self.base_paths {
+            stream_mgr::add_base_path(path).map_err(failed(EngineStep::StreamMgr))?;
+        }
+
+        if let Some(language) = &self.language {
+            stream_mgr::set_current_language(language).map_err(failed(EngineStep::Language))?;
+        }
+
+        sound_engine::init(&mut self.init_settings, &mut self.platform_init_settings)
+            .map_err(failed(EngineStep::SoundEngine))?;
+        engine.sound_engine = true;
+
+        if let Some(settings) = &mut self.music_settings {
+            music_engine::init(settings).map_err(failed(EngineStep::MusicEngine))?;
+            engine.music_engine = true;
+        }
+
+        // Terminated along with the sound engine.
+        if let Some(settings) = &self.spatial_audio_settings {
+            spatial_audio::init(settings).map_err(failed(EngineStep::SpatialAudio))?;
+        }
+
+        #[cfg(not(wwrelease))]
+        if let Some(settings) = &self.comm_settings {
+            communication::init(settings).map_err(failed(EngineStep::Communication))?;
+            engine.communication = true;
+        }
+
+        Ok(engine)
+    }
+}
+
+fn failed(step: EngineStep) -> impl FnOnce(AkResult) -> EngineError {
+    move |result| EngineError { step, result }
+}
+
+/// Guard over the Wwise modules initialized by [EngineBuilder::init].
+///
+/// Dropping it terminates the modules in reverse order of initialization. Only one [Engine] can be
+/// alive at a time; once it is dropped, a new one can be initialized in the same process.
+#[derive(Debug)]
+pub struct Engine {
+    memory_mgr: bool,
+    stream_mgr: bool,
+    custom_stream_mgr: bool,
+    sound_engine: bool,
+    music_engine: bool,
+    #[cfg(not(wwrelease))]
+    communication: bool,
+}
+
+impl Engine {
+    /// Shorthand for [EngineBuilder::new].
+    pub fn builder<T: AsRef<str>>(bank_location: T) -> EngineBuilder {
+        EngineBuilder::new(bank_location)
+    }
+
+    /// Whether an [Engine] is currently alive.
+    pub fn is_alive() -> bool {
+        ENGINE_ALIVE.load(Ordering::SeqCst)
+    }
+
+    /// Terminate all the modules now. Same as dropping the engine.
+    pub fn term(self) {}
+}
+
+impl Drop for Engine {
+    fn drop(&mut self) {
+        #[cfg(not(wwrelease))]
+        if self.communication {
+            communication::term();
+        }
+
+        if self.music_engine {
+            music_engine::term();
+        }
+
+        if self.sound_engine {
+            sound_engine::term();
+        }
+
+        if self.custom_stream_mgr {
+            stream_mgr::term_custom_stream_mgr();
+        } else if self.stream_mgr {
+            stream_mgr::term_default_stream_mgr();
+        }
+
+        if self.memory_mgr {
+            memory_mgr::term();
+        }
+
+        ENGINE_ALIVE.store(false, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,174 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Game objects registered for as long as their handle lives.
+
+use crate::game_syncs::{post_trigger, set_switch, SetRtpcValue};
+use crate::query_params::{get_position, get_rtpc_value, get_switch, RtpcValueType};
+use crate::sound_engine::{
+    add_listener, register_game_obj, register_named_game_obj, remove_listener, set_listeners,
+    set_position, stop_all, unregister_game_obj, PostEvent,
+};
+use crate::{
+    sound_engine, AkGameObjectID, AkID, AkResult, AkRtpcValue, AkSoundPosition, AkSwitchStateID,
+};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// First ID given to a [GameObject].
+///
+/// IDs below this one are never allocated by [GameObject::new] and [GameObject::named], so they
+/// stay available for game objects registered by hand with
+/// [sound_engine::register_game_obj](crate::sound_engine::register_game_obj).
+pub const FIRST_ALLOCATED_GAME_OBJECT_ID: AkGameObjectID = 1 << 32;
+
+static NEXT_GAME_OBJECT_ID: AtomicU64 = AtomicU64::new(FIRST_ALLOCATED_GAME_OBJECT_ID);
+
+/// A game object with a unique ID, registered to the sound engine on creation and unregistered
+/// when dropped.
+///
+/// Use [GameObject::id] to pass it to functions taking a raw [AkGameObjectID].
+///
+/// *Remark* Dropping a [GameObject] after the sound engine has been terminated does nothing.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct GameObject {
+    id: AkGameObjectID,
+}
+
+impl GameObject {
+    /// Allocate a new ID and register it as a game object.
+    ///
+    /// *Return*
+    /// > - The registered game object if successful
+    /// > - [AK_Fail](AkResult::AK_Fail) otherwise
+    ///
+    /// *See also* [sound_engine::register_game_obj](crate::sound_engine::register_game_obj)
+    pub fn new() -> Result<Self, AkResult> {
+        let id = next_id();
+        register_game_obj(id)?;
+        Ok(Self { id })
+    }
+
+    /// Allocate a new ID and register it as a game object with a name.
+    ///
+    /// The name is just for monitoring purpose, and is not forwarded to Wwise when the `wwrelease`
+    /// cfg flag is on.
+    ///
+    /// *See also* [sound_engine::register_named_game_obj](crate::sound_engine::register_named_game_obj)
+    pub fn named<T: AsRef<str>>(name: T) -> Result<Self, AkResult> {
+        let id = next_id();
+        register_named_game_obj(id, name)?;
+        Ok(Self { id })
+    }
+
+    /// The ID of this game object.
+    pub fn id(&self) -> AkGameObjectID {
+        self.id
+    }
+
+    /// See [sound_engine::set_position](crate::sound_engine::set_position).
+    pub fn set_position<T: Into<AkSoundPosition>>(&self, position: T) -> Result<(), AkResult> {
+        set_position(self.id, position)
+    }
+
+    /// See [query_params::get_position](crate::query_params::get_position).
+    pub fn position(&self) -> Result<AkSoundPosition, AkResult> {
+        get_position(self.id)
+    }
+
+    /// Use this game object as a listener for all other game objects that still use the default
+    /// set of listeners.
+    ///
+    /// See [sound_engine::add_default_listener](crate::sound_engine::add_default_listener).
+    pub fn make_default_listener(&self) -> Result<(), AkResult> {
+        sound_engine::add_default_listener(self.id)
+    }
+
+    /// See [sound_engine::set_listeners](crate::sound_engine::set_listeners).
+    pub fn set_listeners(&self, listeners: &[&GameObject]) -> Result<(), AkResult> {
+        let listener_ids: Vec<AkGameObjectID> = listeners.iter().map(|l| l.id).collect();
+        set_listeners(self.id, &listener_ids)
+    }
+
+    /// See [sound_engine::add_listener](crate::sound_engine::add_listener).
+    pub fn add_listener(&self, listener: &GameObject) -> Result<(), AkResult> {
+        add_listener(self.id, listener.id)
+    }
+
+    /// See [sound_engine::remove_listener](crate::sound_engine::remove_listener).
+    pub fn remove_listener(&self, listener: &GameObject) -> Result<(), AkResult> {
+        remove_listener(self.id, listener.id)
+    }
+
+    /// Set an RTPC on this game object only.
+    ///
+    /// Use [SetRtpcValue] with [for_target](SetRtpcValue::for_target) for interpolation options.
+    pub fn set_rtpc<'a, T: Into<AkID<'a>>>(
+        &self,
+        rtpc_id: T,
+        value: AkRtpcValue,
+    ) -> Result<(), AkResult> {
+        SetRtpcValue::new(rtpc_id, value).for_target(self.id).set()
+    }
+
+    /// Get the value of an RTPC on this game object, or the global one if it has not been set on
+    /// this game object.
+    ///
+    /// See [query_params::get_rtpc_value](crate::query_params::get_rtpc_value).
+    pub fn rtpc<'a, T: Into<AkID<'a>>>(&self, rtpc_id: T) -> Result<RtpcValueType, AkResult> {
+        get_rtpc_value(
+            rtpc_id,
+            Some(self.id),
+            None,
+            RtpcValueType::GameObject(AkRtpcValue::default()),
+        )
+    }
+
+    /// See [game_syncs::set_switch](crate::game_syncs::set_switch).
+    pub fn set_switch<'a, T: Into<AkID<'a>>>(
+        &self,
+        switch_group: T,
+        switch_id: T,
+    ) -> Result<(), AkResult> {
+        set_switch(switch_group, switch_id, self.id)
+    }
+
+    /// See [query_params::get_switch](crate::query_params::get_switch).
+    pub fn switch<'a, T: Into<AkID<'a>>>(
+        &self,
+        switch_group: T,
+    ) -> Result<AkSwitchStateID, AkResult> {
+        get_switch(switch_group, self.id)
+    }
+
+    /// See [game_syncs::post_trigger](crate::game_syncs::post_trigger).
+    pub fn post_trigger<'a, T: Into<AkID<'a>>>(&self, trigger: T) -> Result<(), AkResult> {
+        post_trigger(trigger, self.id)
+    }
+
+    /// Prepare posting `event_id` on this game object.
+    ///
+    /// *See also* [PostEvent]
+    pub fn post_event<'a, T: Into<AkID<'a>>>(&self, event_id: T) -> PostEvent<'a> {
+        PostEvent::new(self.id, event_id)
+    }
+
+    /// Stop everything playing on this game object.
+    pub fn stop_all(&self) {
+        stop_all(Some(self.id))
+    }
+}
+
+impl Drop for GameObject {
+    fn drop(&mut self) {
+        if sound_engine::is_initialized() {
+            if let Err(akr) = unregister_game_obj(self.id) {
+                log::warn!("Couldn't unregister game object {}: {}", self.id, akr);
+            }
+        }
+    }
+}
+
+fn next_id() -> AkGameObjectID {
+    NEXT_GAME_OBJECT_ID.fetch_add(1, Ordering::Relaxed)
+}
@@ -0,0 +1,244 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Reload SoundBanks while the game is running, when they are generated again.
+//!
+//! A [BankWatcher] is opt-in: create one once the default streaming manager is initialized, then
+//! call [BankWatcher::poll] regularly, for instance once per frame. Bank files are found like the
+//! default streaming manager finds them (see [resolve_file](crate::stream_mgr::resolve_file)), in
+//! the [current language](crate::stream_mgr::current_language) subfolder first.
+//!
+//! Only the banks loaded by name through [Bank](crate::bank::Bank) handles are watched; the handles
+//! stay valid across reloads. A bank that fails to reload stays unloaded, as reported by
+//! [Bank::is_loaded](crate::bank::Bank::is_loaded), until its file changes again and reloads
+//! successfully.
+//!
+//! *Remark* Reloading a bank stops the sounds playing from it. Looping events the game wants to
+//! keep playing can be posted with [BankWatcher::persist] to be posted again after each reload.
+
+use crate::sound_engine::{PlayingHandle, PostEvent};
+use crate::{bank, stream_mgr};
+use crate::{AkBankID, AkCurveInterpolation, AkPlayingID, AkResult, AkTimeMs};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Key of an event posted with [BankWatcher::persist].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PersistentEventKey(usize);
+
+struct PersistentEvent {
+    event: PostEvent<'static>,
+    handle: Option<PlayingHandle>,
+}
+
+/// Watches the modification time of the `.bnk` files of the loaded banks, and reloads the banks
+/// whose file changed.
+///
+/// *See also* [module documentation](crate::hot_reload)
+pub struct BankWatcher {
+    base_paths: Option<Vec<PathBuf>>,
+    interval: Duration,
+    last_poll: Option<Instant>,
+    modified: HashMap<AkBankID, SystemTime>,
+    persistent_events: HashMap<PersistentEventKey, PersistentEvent>,
+    next_key: usize,
+}
+
+impl BankWatcher {
+    /// Watch the banks found in the [base paths](crate::stream_mgr::base_paths) of the default
+    /// streaming manager.
+    pub fn new() -> Self {
+        Self::with_base_paths_opt(None)
+    }
+
+    /// Watch the banks found in `base_paths`, in search order, and in their
+    /// [current language](crate::stream_mgr::current_language) subfolder.
+    ///
+    /// Use this instead of [new](Self::new) when the banks are not read by the default streaming
+    /// manager, but by a [LowLevelIo](crate::low_level_io::LowLevelIo) serving the same folders.
+    pub fn with_base_paths<I, P>(base_paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        Self::with_base_paths_opt(Some(
+            base_paths
+                .into_iter()
+                .map(|path| path.as_ref().to_path_buf())
+                .collect(),
+        ))
+    }
+
+    fn with_base_paths_opt(base_paths: Option<Vec<PathBuf>>) -> Self {
+        Self {
+            base_paths,
+            interval: Duration::from_secs(1),
+            last_poll: None,
+            modified: HashMap::new(),
+            persistent_events: HashMap::new(),
+            next_key: 0,
+        }
+    }
+
+    /// Minimum time between two checks of the bank files; calls to [poll](Self::poll) in between
+    /// do nothing.
+    ///
+    /// Default: 1 second
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Post `event`, and post it again each time a bank is reloaded.
+    ///
+    /// *Return* The key of the persistent event if it could be posted, or the error of
+    /// [PostEvent::post].
+    ///
+    /// *See also* [unpersist](Self::unpersist)
+    pub fn persist(&mut self, event: PostEvent<'static>) -> Result<PersistentEventKey, AkResult> {
+        let handle = event.post()?;
+        let key = PersistentEventKey(self.next_key);
+        self.next_key += 1;
+        self.persistent_events.insert(
+            key,
+            PersistentEvent {
+                event,
+                handle: Some(handle),
+            },
+        );
+        Ok(key)
+    }
+
+    /// The current playing ID of a persistent event, which changes each time it is posted again.
+    ///
+    /// *Return* [None] if `key` was unpersisted, or if the event could not be posted again after
+    /// the last reload.
+    pub fn playing_id(&self, key: PersistentEventKey) -> Option<AkPlayingID> {
+        self.persistent_events
+            .get(&key)
+            .and_then(|persistent| persistent.handle.as_ref())
+            .map(PlayingHandle::playing_id)
+    }
+
+    /// Stop a persistent event, fading out over `fade_ms` milliseconds following `curve`, and
+    /// don't post it again anymore.
+    pub fn unpersist(
+        &mut self,
+        key: PersistentEventKey,
+        fade_ms: AkTimeMs,
+        curve: AkCurveInterpolation,
+    ) {
+        if let Some(PersistentEvent {
+            handle: Some(handle),
+            ..
+        }) = self.persistent_events.remove(&key)
+        {
+            handle.stop(fade_ms, curve);
+        }
+    }
+
+    /// Reload the banks whose file changed since the last check, then post the persistent events
+    /// again if any bank was reloaded.
+    ///
+    /// Successes and failures are reported through the [log] crate.
+    ///
+    /// *Return* The IDs of the banks that were reloaded successfully.
+    pub fn poll(&mut self) -> Vec<AkBankID> {
+        let now = Instant::now();
+        if matches!(self.last_poll, Some(last_poll) if now - last_poll < self.interval) {
+            return Vec::new();
+        }
+        self.last_poll = Some(now);
+
+        let banks = bank::named_banks();
+        self.modified
+            .retain(|id, _| banks.iter().any(|(bank_id, _)| bank_id == id));
+
+        let mut reloaded = Vec::new();
+        for (id, name) in banks {
+            let path = match self.bank_path(&name) {
+                Some(path) => path,
+                None => {
+                    log::debug!("Not watching bank {}: file not found", name);
+                    continue;
+                }
+            };
+            let modified = match path.metadata().and_then(|metadata| metadata.modified()) {
+                Ok(modified) => modified,
+                Err(e) => {
+                    log::debug!("Not watching bank {} ({}): {}", name, path.display(), e);
+                    continue;
+                }
+            };
+
+            let previous = self.modified.insert(id, modified);
+            if previous.map_or(false, |previous| previous != modified) {
+                match bank::reload(id) {
+                    Ok(()) => {
+                        log::info!("Reloaded bank {}", name);
+                        reloaded.push(id);
+                    }
+                    Err(akr) => log::error!(
+                        "Couldn't reload bank {}, it stays unloaded until its file changes again: {}",
+                        name,
+                        akr
+                    ),
+                }
+            }
+        }
+
+        if !reloaded.is_empty() {
+            self.post_persistent_events();
+        }
+        reloaded
+    }
+
+    /// The file of the bank `name`, localized or not.
+    fn bank_path(&self, name: &str) -> Option<PathBuf> {
+        let file_name = if name.ends_with(".bnk") {
+            name.to_string()
+        } else {
+            format!("{}.bnk", name)
+        };
+
+        match &self.base_paths {
+            None => stream_mgr::resolve_file(&file_name, true)
+                .or_else(|| stream_mgr::resolve_file(&file_name, false)),
+            Some(base_paths) => {
+                let language = stream_mgr::current_language();
+                base_paths
+                    .iter()
+                    .flat_map(|base_path| {
+                        [
+                            base_path.join(&language).join(&file_name),
+                            base_path.join(&file_name),
+                        ]
+                    })
+                    .find(|path| path.is_file())
+            }
+        }
+    }
+
+    fn post_persistent_events(&mut self) {
+        for persistent in self.persistent_events.values_mut() {
+            if let Some(handle) = persistent.handle.take() {
+                handle.stop(0, AkCurveInterpolation::AkCurveInterpolation_Linear);
+            }
+            match persistent.event.post() {
+                Ok(handle) => {
+                    log::info!("Posted persistent event {} again", handle);
+                    persistent.handle = Some(handle);
+                }
+                Err(akr) => log::error!("Couldn't post persistent event again: {}", akr),
+            }
+        }
+    }
+}
+
+impl Default for BankWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
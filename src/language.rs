@@ -0,0 +1,324 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Switch the language of localized SoundBanks and streamed media while the game is running.
+//!
+//! Localized banks and media are generated in one folder per language (`English(US)`,
+//! `French(France)`...) next to the banks that are not localized. A [LanguageManager] finds the
+//! available languages by scanning these folders in the
+//! [base paths](crate::stream_mgr::base_paths) of the default streaming manager, sets the
+//! [current language](crate::stream_mgr::current_language), and reloads the localized banks
+//! loaded through [Bank](crate::bank::Bank) handles when it changes.
+//!
+//! Languages that were not generated can fall back on other ones, for instance `French(Canada)` on
+//! `French(France)` then `English(US)`: see [LanguageManager::with_fallbacks].
+
+use crate::{bank, stream_mgr, AkBankID, AkResult};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Key of an observer added with [LanguageManager::add_observer].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LanguageObserverKey(usize);
+
+/// A change of the current language, as notified to the observers of a [LanguageManager].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageChange {
+    /// The language before the change, empty if none was set.
+    pub previous: String,
+    /// The language asked for.
+    pub requested: String,
+    /// The language that was set: `requested`, or the first of its fallbacks that is available.
+    pub current: String,
+    /// The localized banks that were reloaded in the new language.
+    pub reloaded: Vec<AkBankID>,
+    /// The localized banks that couldn't be reloaded in the new language, with the reason.
+    ///
+    /// Banks not found in the new language are reported with
+    /// [AK_FileNotFound](AkResult::AK_FileNotFound) and stay loaded in the previous one; the others
+    /// are unloaded, see [Bank::is_loaded](crate::bank::Bank::is_loaded).
+    pub failed: Vec<(AkBankID, AkResult)>,
+}
+
+type LanguageObserver = Box<dyn FnMut(&LanguageChange) + Send>;
+
+/// Finds the available languages and switches between them, reloading the localized banks.
+///
+/// *See also* [module documentation](crate::language)
+pub struct LanguageManager {
+    base_paths: Vec<PathBuf>,
+    available: Vec<String>,
+    fallbacks: HashMap<String, Vec<String>>,
+    observers: HashMap<LanguageObserverKey, LanguageObserver>,
+    next_key: usize,
+}
+
+impl LanguageManager {
+    /// Find the languages available in the [base paths](crate::stream_mgr::base_paths) of the
+    /// default streaming manager.
+    ///
+    /// *Remark* The default streaming manager must be initialized, and all the base paths added,
+    /// before creating the manager; otherwise, call [rescan](Self::rescan) afterwards.
+    pub fn new() -> Self {
+        Self::with_base_paths(stream_mgr::base_paths())
+    }
+
+    /// Find the languages available in `base_paths`, in search order.
+    ///
+    /// Use this instead of [new](Self::new) when the files are not read by the default streaming
+    /// manager, but by a [LowLevelIo](crate::low_level_io::LowLevelIo) serving the same folders.
+    pub fn with_base_paths<I, P>(base_paths: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut manager = Self {
+            base_paths: base_paths
+                .into_iter()
+                .map(|path| path.as_ref().to_path_buf())
+                .collect(),
+            available: Vec::new(),
+            fallbacks: HashMap::new(),
+            observers: HashMap::new(),
+            next_key: 0,
+        };
+        manager.rescan();
+        manager
+    }
+
+    /// Fall back on the first available language of `fallbacks` when `language` is not available.
+    ///
+    /// Fallbacks are followed in order, and so are their own fallbacks: with `French(Canada)`
+    /// falling back on `French(France)`, and `French(France)` on `English(US)`, asking for
+    /// `French(Canada)` sets `English(US)` if only that one was generated.
+    pub fn with_fallbacks<T, I, F>(mut self, language: T, fallbacks: I) -> Self
+    where
+        T: AsRef<str>,
+        I: IntoIterator<Item = F>,
+        F: AsRef<str>,
+    {
+        self.fallbacks.insert(
+            language.as_ref().to_string(),
+            fallbacks
+                .into_iter()
+                .map(|fallback| fallback.as_ref().to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Scan the base paths again for language folders, for instance after a DLC or patch folder was
+    /// added.
+    ///
+    /// A language folder is a subfolder of a base path containing banks or streamed media.
+    pub fn rescan(&mut self) {
+        self.available.clear();
+        for base_path in &self.base_paths {
+            let entries = match base_path.read_dir() {
+                Ok(entries) => entries,
+                Err(e) => {
+                    log::warn!("Couldn't scan {} for languages: {}", base_path.display(), e);
+                    continue;
+                }
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let language = match path.file_name().and_then(|name| name.to_str()) {
+                    Some(language) if path.is_dir() && has_sound_files(&path) => language,
+                    _ => continue,
+                };
+                if !self.available.iter().any(|known| known == language) {
+                    self.available.push(language.to_string());
+                }
+            }
+        }
+
+        self.available.sort();
+        log::debug!("Available languages: {}", self.available.join(", "));
+    }
+
+    /// The languages found in the base paths, sorted by name.
+    pub fn available_languages(&self) -> &[String] {
+        &self.available
+    }
+
+    /// Whether a folder was found for `language` in the base paths.
+    pub fn is_available<T: AsRef<str>>(&self, language: T) -> bool {
+        self.available
+            .iter()
+            .any(|known| known == language.as_ref())
+    }
+
+    /// The current language of the streaming manager, empty if none was set.
+    ///
+    /// *See also* [stream_mgr::current_language]
+    pub fn current_language(&self) -> String {
+        stream_mgr::current_language()
+    }
+
+    /// The language that [set_language](Self::set_language) sets when asked for `language`: itself
+    /// if it is available, or the first available language in its fallback chain.
+    ///
+    /// *Return* [None] if neither `language` nor its fallbacks are available.
+    pub fn resolve<T: AsRef<str>>(&self, language: T) -> Option<&str> {
+        let mut chain = vec![language.as_ref()];
+        let mut i = 0;
+        while i < chain.len() {
+            if let Some(language) = self.available.iter().find(|known| *known == chain[i]) {
+                return Some(language);
+            }
+            for fallback in self.fallbacks.get(chain[i]).into_iter().flatten() {
+                if !chain.contains(&fallback.as_str()) {
+                    chain.push(fallback);
+                }
+            }
+            i += 1;
+        }
+        None
+    }
+
+    /// Notify `observer` each time [set_language](Self::set_language) changes the current language.
+    ///
+    /// *See also* [remove_observer](Self::remove_observer)
+    pub fn add_observer<F: FnMut(&LanguageChange) + Send + 'static>(
+        &mut self,
+        observer: F,
+    ) -> LanguageObserverKey {
+        let key = LanguageObserverKey(self.next_key);
+        self.next_key += 1;
+        self.observers.insert(key, Box::new(observer));
+        key
+    }
+
+    /// Stop notifying an observer added with [add_observer](Self::add_observer).
+    ///
+    /// *Return* Whether the observer was still registered.
+    pub fn remove_observer(&mut self, key: LanguageObserverKey) -> bool {
+        self.observers.remove(&key).is_some()
+    }
+
+    /// Set the current language to `language`, or the first available language of its fallback
+    /// chain, then reload the localized banks loaded by name through [Bank](crate::bank::Bank)
+    /// handles, and notify the observers.
+    ///
+    /// Nothing is reloaded nor notified if the resolved language is already the current one.
+    /// Banks that can't be reloaded are reported in [LanguageChange::failed] and through the [log]
+    /// crate.
+    ///
+    /// A bank is only unloaded once its file is found in the new language; as the sound engine
+    /// can't load two banks with the same ID, it is then unloaded right before being loaded again.
+    ///
+    /// *Return*
+    /// > - The language that was set if successful
+    /// > - [AK_InvalidLanguage](AkResult::AK_InvalidLanguage) if neither `language` nor its
+    /// fallbacks are available
+    /// > - The error of [stream_mgr::set_current_language] otherwise
+    ///
+    /// *Remark* Reloading a bank stops the sounds playing from it.
+    pub fn set_language<T: AsRef<str>>(&mut self, language: T) -> Result<String, AkResult> {
+        let requested = language.as_ref();
+        let current = match self.resolve(requested) {
+            Some(current) => current.to_string(),
+            None => {
+                log::warn!("Language {} is not available", requested);
+                return Err(AkResult::AK_InvalidLanguage);
+            }
+        };
+
+        let previous = stream_mgr::current_language();
+        if previous == current {
+            return Ok(current);
+        }
+
+        let localized: Vec<_> = bank::named_banks()
+            .into_iter()
+            .filter(|(_, name)| self.is_localized(name))
+            .collect();
+
+        stream_mgr::set_current_language(&current)?;
+        if current == requested {
+            log::info!("Language set to {}", current);
+        } else {
+            log::info!("Language set to {} (fallback for {})", current, requested);
+        }
+
+        let mut reloaded = Vec::new();
+        let mut failed = Vec::new();
+        for (id, name) in localized {
+            if !self.has_bank(&current, &name) {
+                log::error!(
+                    "Couldn't find bank {} in {}, keeping it in {}",
+                    name,
+                    current,
+                    previous
+                );
+                failed.push((id, AkResult::AK_FileNotFound));
+                continue;
+            }
+
+            match bank::reload(id) {
+                Ok(()) => {
+                    log::info!("Reloaded bank {} in {}", name, current);
+                    reloaded.push(id);
+                }
+                Err(akr) => {
+                    log::error!("Couldn't reload bank {} in {}: {}", name, current, akr);
+                    failed.push((id, akr));
+                }
+            }
+        }
+
+        let change = LanguageChange {
+            previous,
+            requested: requested.to_string(),
+            current: current.clone(),
+            reloaded,
+            failed,
+        };
+        for observer in self.observers.values_mut() {
+            observer(&change);
+        }
+        Ok(current)
+    }
+
+    /// Whether the bank `name` is found in a language folder.
+    fn is_localized(&self, name: &str) -> bool {
+        self.available
+            .iter()
+            .any(|language| self.has_bank(language, name))
+    }
+
+    /// Whether the bank `name` is found in the folder of `language`.
+    fn has_bank(&self, language: &str, name: &str) -> bool {
+        let file_name = if name.ends_with(".bnk") {
+            name.to_string()
+        } else {
+            format!("{}.bnk", name)
+        };
+
+        self.base_paths
+            .iter()
+            .any(|base_path| base_path.join(language).join(&file_name).is_file())
+    }
+}
+
+impl Default for LanguageManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn has_sound_files(folder: &Path) -> bool {
+    folder.read_dir().map_or(false, |entries| {
+        entries.flatten().any(|entry| {
+            let path = entry.path();
+            path.is_file()
+                && matches!(
+                    path.extension().and_then(|extension| extension.to_str()),
+                    Some("bnk" | "wem")
+                )
+        })
+    })
+}
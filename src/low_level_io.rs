@@ -0,0 +1,312 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Implement the Low-Level I/O in Rust, to serve the files of the sound engine from any storage.
+//!
+//! The Stream Manager reads banks and streamed media through a Low-Level I/O. Instead of the
+//! default one reading files from a folder (see
+//! [init_default_stream_mgr](crate::stream_mgr::init_default_stream_mgr)), a [LowLevelIo]
+//! implementation can be given to [init_custom_stream_mgr](crate::stream_mgr::init_custom_stream_mgr)
+//! or [EngineBuilder::with_low_level_io](crate::engine::EngineBuilder::with_low_level_io) to read
+//! them from a virtual file system, an archive, an encrypted asset store...
+
+use crate::bindings::root::{
+    AkFileID, AkInt64, AkOSChar, AkUInt64, AkUIntPtr, InitCustomStreamMgr,
+    RriseLowLevelIOCallbacks, TermCustomStreamMgr, AKRESULT,
+};
+use crate::settings::AkDeviceSettings;
+use crate::{ak_call_result, from_os_char, AkCodecID, AkID, AkResult, AkUInt32, AKCODECID_BANK};
+use std::collections::HashMap;
+use std::os::raw::c_void;
+use std::sync::Mutex;
+
+#[doc(inline)]
+pub use crate::bindings::root::AkOpenMode;
+
+/// A file opened by a [LowLevelIo].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct LowLevelFile {
+    /// Identifies the file in the next calls to the [LowLevelIo]; its meaning is up to the
+    /// implementation (index in a table, file descriptor...).
+    pub handle: usize,
+    /// Size of the file in bytes.
+    pub size: u64,
+}
+
+/// Low-Level I/O implemented in Rust.
+///
+/// The methods are called from the threads of the sound engine and Stream Manager (the I/O thread
+/// of the streaming device, the Bank Manager thread...), possibly at the same time.
+///
+/// *Remark* All calls are blocking: the streaming device must use the `AK_SCHEDULER_BLOCKING`
+/// scheduler, which is the default of [AkDeviceSettings].
+pub trait LowLevelIo: Send + Sync + 'static {
+    /// Open `file`, by name or by ID.
+    ///
+    /// Files are opened by name (a bank name with its extension, a streamed file name...), or by ID
+    /// when banks are loaded by ID or media is streamed from generated files named after their ID.
+    /// `codec` is [AKCODECID_BANK] for banks, or the codec of streamed media (for instance
+    /// [AKCODECID_VORBIS](crate::AKCODECID_VORBIS)), which tells which file extension an ID stands
+    /// for. `is_language_specific` is set for files found in the folder of the current language.
+    ///
+    /// *Return* The opened file, or an error such as [AK_FileNotFound](AkResult::AK_FileNotFound).
+    fn open(
+        &self,
+        file: AkID,
+        mode: AkOpenMode,
+        codec: AkCodecID,
+        is_language_specific: bool,
+    ) -> Result<LowLevelFile, AkResult>;
+
+    /// Fill `buffer` with the bytes of the file `handle` from `position`.
+    ///
+    /// *Return* [AK_Fail](AkResult::AK_Fail) or another error if `buffer` couldn't be filled.
+    fn read(&self, handle: usize, position: u64, buffer: &mut [u8]) -> Result<(), AkResult>;
+
+    /// Write `data` to the file `handle` at `position`.
+    ///
+    /// Only needed by plug-ins writing files, such as the Recorder effect.
+    ///
+    /// Default: [AK_NotImplemented](AkResult::AK_NotImplemented)
+    fn write(&self, _handle: usize, _position: u64, _data: &[u8]) -> Result<(), AkResult> {
+        Err(AkResult::AK_NotImplemented)
+    }
+
+    /// Close the file `handle`.
+    fn close(&self, handle: usize) -> Result<(), AkResult>;
+
+    /// Size in bytes of the blocks of the file `handle`: positions and sizes of reads and writes
+    /// are multiples of it.
+    ///
+    /// Default: `1`
+    fn block_size(&self, _handle: usize) -> AkUInt32 {
+        1
+    }
+}
+
+struct LowLevelIoCookie(*mut Box<dyn LowLevelIo>);
+
+// Safety: the cookie points to a Send + Sync LowLevelIo
+unsafe impl Send for LowLevelIoCookie {}
+
+static LOW_LEVEL_IO: Mutex<Option<LowLevelIoCookie>> = Mutex::new(None);
+
+/// Create the streaming device and file location resolver forwarding to `io`.
+pub(crate) fn init_device(
+    device_settings: &mut AkDeviceSettings,
+    io: Box<dyn LowLevelIo>,
+) -> Result<(), AkResult> {
+    let mut installed = LOW_LEVEL_IO.lock().unwrap();
+    if installed.is_some() {
+        return Err(AkResult::AK_Fail);
+    }
+
+    let cookie = Box::into_raw(Box::new(io));
+    let callbacks = RriseLowLevelIOCallbacks {
+        cookie: cookie as *mut c_void,
+        open: Some(open),
+        read: Some(read),
+        write: Some(write),
+        close: Some(close),
+        blockSize: Some(block_size),
+    };
+    let device_settings = device_settings.as_ak();
+    match ak_call_result![InitCustomStreamMgr(&device_settings, &callbacks)] {
+        Ok(()) => {
+            *installed = Some(LowLevelIoCookie(cookie));
+            Ok(())
+        }
+        Err(akr) => {
+            drop(unsafe { Box::from_raw(cookie) });
+            Err(akr)
+        }
+    }
+}
+
+/// Whether a [LowLevelIo] is installed by [init_device].
+pub(crate) fn is_installed() -> bool {
+    LOW_LEVEL_IO.lock().unwrap().is_some()
+}
+
+/// Destroy the streaming device and the Stream Manager, then drop the [LowLevelIo].
+pub(crate) fn term_device() {
+    unsafe {
+        TermCustomStreamMgr();
+    }
+    if let Some(LowLevelIoCookie(cookie)) = LOW_LEVEL_IO.lock().unwrap().take() {
+        drop(unsafe { Box::from_raw(cookie) });
+    }
+}
+
+unsafe fn io<'a>(cookie: *mut c_void) -> &'a dyn LowLevelIo {
+    &**(cookie as *const Box<dyn LowLevelIo>)
+}
+
+fn to_akresult(result: Result<(), AkResult>) -> AKRESULT {
+    match result {
+        Ok(()) => AkResult::AK_Success,
+        Err(akr) => akr,
+    }
+}
+
+unsafe extern "C" fn open(
+    cookie: *mut c_void,
+    file_name: *const AkOSChar,
+    file_id: AkFileID,
+    mode: AkOpenMode,
+    codec: AkCodecID,
+    is_language_specific: bool,
+    out_handle: *mut AkUIntPtr,
+    out_file_size: *mut AkInt64,
+) -> AKRESULT {
+    let name;
+    let file = if file_name.is_null() {
+        AkID::ID(file_id)
+    } else {
+        name = from_os_char(file_name);
+        AkID::Name(&name)
+    };
+
+    match io(cookie).open(file, mode, codec, is_language_specific) {
+        Ok(file) => {
+            *out_handle = file.handle as AkUIntPtr;
+            *out_file_size = file.size as AkInt64;
+            AkResult::AK_Success
+        }
+        Err(akr) => akr,
+    }
+}
+
+unsafe extern "C" fn read(
+    cookie: *mut c_void,
+    handle: AkUIntPtr,
+    position: AkUInt64,
+    out_buffer: *mut c_void,
+    size: AkUInt32,
+) -> AKRESULT {
+    let buffer = std::slice::from_raw_parts_mut(out_buffer as *mut u8, size as usize);
+    to_akresult(io(cookie).read(handle as usize, position, buffer))
+}
+
+unsafe extern "C" fn write(
+    cookie: *mut c_void,
+    handle: AkUIntPtr,
+    position: AkUInt64,
+    data: *const c_void,
+    size: AkUInt32,
+) -> AKRESULT {
+    let data = std::slice::from_raw_parts(data as *const u8, size as usize);
+    to_akresult(io(cookie).write(handle as usize, position, data))
+}
+
+unsafe extern "C" fn close(cookie: *mut c_void, handle: AkUIntPtr) -> AKRESULT {
+    to_akresult(io(cookie).close(handle as usize))
+}
+
+unsafe extern "C" fn block_size(cookie: *mut c_void, handle: AkUIntPtr) -> AkUInt32 {
+    io(cookie).block_size(handle as usize)
+}
+
+/// [LowLevelIo] serving banks and streamed media embedded in the executable.
+///
+/// Files are looked up by their path relative to the bank folder, with `/` separators: bank and
+/// streamed file names (`Init.bnk`, `123456.wem`; files opened by ID get the `.bnk` extension for
+/// banks and `.wem` for media), prefixed with the
+/// [current language](crate::stream_mgr::current_language) folder for localized files
+/// (`English(US)/VO.bnk`).
+///
+/// Use [embed_files!](crate::embed_files) to embed a list of files, or the `embed` feature of
+/// rrise-headers to embed a whole bank folder.
+///
+/// *See also* [init_embedded_stream_mgr](crate::stream_mgr::init_embedded_stream_mgr)
+#[derive(Debug, Default, Clone)]
+pub struct EmbeddedIo {
+    files: Vec<&'static [u8]>,
+    paths: HashMap<String, usize>,
+}
+
+impl EmbeddedIo {
+    /// Serve `files`, by path.
+    pub fn new(files: HashMap<&'static str, &'static [u8]>) -> Self {
+        Self::from_files(files)
+    }
+
+    /// Serve `files`, by path.
+    pub fn from_files<I: IntoIterator<Item = (&'static str, &'static [u8])>>(files: I) -> Self {
+        let mut io = Self::default();
+        for (path, data) in files {
+            io.paths.insert(normalize_path(path), io.files.len());
+            io.files.push(data);
+        }
+        io
+    }
+
+    /// Whether a file is served at `path`.
+    pub fn contains<T: AsRef<str>>(&self, path: T) -> bool {
+        self.paths.contains_key(&normalize_path(path.as_ref()))
+    }
+
+    fn find(&self, file: AkID, codec: AkCodecID, is_language_specific: bool) -> Option<usize> {
+        let name = match file {
+            AkID::Name(name) => name.to_string(),
+            AkID::ID(id) if codec == AKCODECID_BANK => format!("{}.bnk", id),
+            AkID::ID(id) => format!("{}.wem", id),
+        };
+        let language = if is_language_specific {
+            crate::stream_mgr::current_language()
+        } else {
+            String::new()
+        };
+        let path = if language.is_empty() {
+            name
+        } else {
+            format!("{}/{}", language, name)
+        };
+
+        self.paths.get(&normalize_path(&path)).copied()
+    }
+}
+
+impl LowLevelIo for EmbeddedIo {
+    fn open(
+        &self,
+        file: AkID,
+        mode: AkOpenMode,
+        codec: AkCodecID,
+        is_language_specific: bool,
+    ) -> Result<LowLevelFile, AkResult> {
+        if mode != AkOpenMode::AK_OpenModeRead {
+            return Err(AkResult::AK_NotImplemented);
+        }
+
+        let handle = self
+            .find(file, codec, is_language_specific)
+            .ok_or(AkResult::AK_FileNotFound)?;
+        Ok(LowLevelFile {
+            handle,
+            size: self.files[handle].len() as u64,
+        })
+    }
+
+    fn read(&self, handle: usize, position: u64, buffer: &mut [u8]) -> Result<(), AkResult> {
+        let data = self
+            .files
+            .get(handle)
+            .ok_or(AkResult::AK_InvalidParameter)?;
+        let start = (position as usize).min(data.len());
+        let available = &data[start..];
+        let len = available.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&available[..len]);
+        buffer[len..].fill(0);
+        Ok(())
+    }
+
+    fn close(&self, _handle: usize) -> Result<(), AkResult> {
+        Ok(())
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    path.replace('\\', "/").trim_start_matches("./").to_string()
+}
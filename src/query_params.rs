@@ -17,8 +17,8 @@
 //! the information being reflected in a Query (such as GetIsGameObjectActive).
 
 use crate::bindings::root::AK::SoundEngine::Query::{
-    GetListenerPosition, GetListeners, GetPosition, GetRTPCValue, GetRTPCValue2, GetState,
-    GetState2, GetSwitch, GetSwitch2, RTPCValue_type,
+    GetListenerPosition, GetListeners, GetPlayingIDsFromGameObject, GetPosition, GetRTPCValue,
+    GetRTPCValue2, GetState, GetState2, GetSwitch, GetSwitch2, RTPCValue_type,
 };
 use crate::{
     ak_call_result, with_cstring, AkGameObjectID, AkID, AkListenerPosition, AkPlayingID, AkResult,
@@ -97,6 +97,32 @@ pub fn get_listener_position(index: u64) -> Result<AkListenerPosition, AkResult>
     Ok(result)
 }
 
+/// Get the list of the playing IDs active on a game object.
+///
+/// *Return* the playing IDs if succeeded, or [AkResult::AK_IDNotFound] if the game object was not registered.
+pub fn get_playing_ids_from_game_object(
+    game_obj: AkGameObjectID,
+) -> Result<Vec<AkPlayingID>, AkResult> {
+    let mut count = 0;
+    ak_call_result![GetPlayingIDsFromGameObject(
+        game_obj,
+        &mut count,
+        std::ptr::null_mut()
+    )]?;
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut result = vec![AK_INVALID_PLAYING_ID; count as usize];
+    ak_call_result![GetPlayingIDsFromGameObject(
+        game_obj,
+        &mut count,
+        result.as_mut_ptr()
+    )]?;
+    result.truncate(count as usize);
+    Ok(result)
+}
+
 /// Get the value of a real-time parameter control.
 ///
 /// An RTPC can have a any combination of a global value, a unique value for each game object, or a unique value for each playing ID.
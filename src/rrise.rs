@@ -4,14 +4,25 @@
 
 #![doc = include_str!("../README.MD")]
 
+#[cfg(feature = "AkAudioInputSource")]
+pub mod audio_input;
+pub mod bank;
+pub mod bank_cache;
+pub mod bank_index;
 #[cfg(not(wwrelease))]
 pub mod communication;
+pub mod engine;
+pub mod game_object;
 pub mod game_syncs;
+pub mod hot_reload;
+pub mod language;
+pub mod low_level_io;
 pub mod memory_mgr;
 pub mod music_engine;
 pub mod query_params;
 pub mod settings;
 pub mod sound_engine;
+pub mod spatial_audio;
 pub mod stream_mgr;
 
 mod bindings;
@@ -49,6 +60,10 @@ pub use bindings::root::AkDeviceID;
 pub use bindings::root::AkFileID;
 /// Game object ID
 pub use bindings::root::AkGameObjectID;
+/// Spatial Audio geometry instance ID
+pub use bindings::root::AkGeometryInstanceID;
+/// Spatial Audio geometry set ID
+pub use bindings::root::AkGeometrySetID;
 /// Image Source ID
 pub use bindings::root::AkImageSourceID;
 /// Low-pass filter type
@@ -73,10 +88,14 @@ pub use bindings::root::AkPluginID;
 pub use bindings::root::AkPluginParamID;
 /// Port number
 pub use bindings::root::AkPortNumber;
+/// Spatial Audio portal ID
+pub use bindings::root::AkPortalID;
 /// Priority
 pub use bindings::root::AkPriority;
 /// Unique (per emitter) identifier for an emitter-listener ray.
 pub use bindings::root::AkRayID;
+/// Spatial Audio room ID
+pub use bindings::root::AkRoomID;
 /// Real time parameter control ID
 pub use bindings::root::AkRtpcID;
 /// Real time parameter control value
@@ -115,6 +134,8 @@ pub use bindings::root::AkChannelConfig;
 #[doc(inline)]
 pub use bindings::root::AkCurveInterpolation;
 #[doc(inline)]
+pub use bindings::root::AkGroupType;
+#[doc(inline)]
 pub use bindings::root::AkListenerPosition;
 #[doc(inline)]
 pub use bindings::root::AkOutputSettings;
@@ -129,6 +150,10 @@ pub use bindings::root::AkTransform;
 #[doc(inline)]
 pub use bindings::root::AkVector;
 #[doc(inline)]
+pub use bindings::root::AK::SoundEngine::AkBankContent;
+#[doc(inline)]
+pub use bindings::root::AK::SoundEngine::PreparationType;
+#[doc(inline)]
 pub use bindings::root::AKRESULT as AkResult;
 
 pub use crate::bindings::root::AkMIDIEvent_tCc;
@@ -140,6 +165,18 @@ pub use crate::bindings::root::AkMIDIEvent_tPitchBend;
 pub use crate::bindings::root::AkMIDIEvent_tProgramChange;
 pub use crate::bindings::root::AkMIDIEvent_tWwiseCmd;
 
+#[doc(inline)]
+pub use bindings::root::AKCODECID_ADPCM;
+#[doc(inline)]
+pub use bindings::root::AKCODECID_AKOPUS_WEM;
+#[doc(inline)]
+pub use bindings::root::AKCODECID_BANK;
+#[doc(inline)]
+pub use bindings::root::AKCODECID_PCM;
+#[doc(inline)]
+pub use bindings::root::AKCODECID_VORBIS;
+#[doc(inline)]
+pub use bindings::root::AK_BANK_PLATFORM_DATA_ALIGNMENT;
 #[doc(inline)]
 pub use bindings::root::AK_DEFAULT_BANK_IO_PRIORITY;
 #[doc(inline)]
@@ -264,6 +301,29 @@ pub(crate) fn to_os_char<T: AsRef<str>>(str: T) -> Vec<OsChar> {
     }
 }
 
+#[doc(hidden)]
+/// Copy a null-terminated string of OsChar (u16 on Windows, i8 == c_char on other platforms).
+///
+/// # Safety
+/// `os_str` must point to a null-terminated string.
+pub(crate) unsafe fn from_os_char(os_str: *const OsChar) -> String {
+    #[cfg(windows)]
+    {
+        let mut len = 0;
+        while *os_str.add(len) != 0 {
+            len += 1;
+        }
+        String::from_utf16_lossy(std::slice::from_raw_parts(os_str, len))
+    }
+
+    #[cfg(not(windows))]
+    {
+        std::ffi::CStr::from_ptr(os_str)
+            .to_string_lossy()
+            .into_owned()
+    }
+}
+
 #[doc(hidden)]
 /// Wraps an unsafe call to Wwise and match its result to a Result<(), AkResult>.
 ///
@@ -296,6 +356,48 @@ macro_rules! ak_call_result {
     };
 }
 
+/// Includes a SoundBank file as a `&'static [u8]` aligned on
+/// [AK_BANK_PLATFORM_DATA_ALIGNMENT], ready to be loaded with
+/// [sound_engine::load_bank_memory_view](crate::sound_engine::load_bank_memory_view) or
+/// [Bank::load_memory_view](crate::bank::Bank::load_memory_view).
+///
+/// The file is located relatively to the current file, like with [include_bytes!].
+///
+/// ```rust,ignore
+/// let bank_id = sound_engine::load_bank_memory_view(include_bank!("banks/Init.bnk"))?;
+/// ```
+#[macro_export]
+macro_rules! include_bank {
+    ($path:expr) => {{
+        // AK_BANK_PLATFORM_DATA_ALIGNMENT is 16 on all supported platforms
+        #[repr(C, align(16))]
+        struct AlignedBank<T: ?Sized>(T);
+        static BANK: &AlignedBank<[u8]> = &AlignedBank(*include_bytes!($path));
+        &BANK.0
+    }};
+}
+
+/// Includes files of a bank folder in a `HashMap<&'static str, &'static [u8]>` keyed by their
+/// path relative to the folder, ready to be served by
+/// [EmbeddedIo](crate::low_level_io::EmbeddedIo).
+///
+/// The folder is located relatively to the current file, like with [include_bytes!].
+///
+/// ```rust,ignore
+/// let files = embed_files!("banks/Linux" => ["Init.bnk", "Music.bnk", "English(US)/VO.bnk", "123456.wem"]);
+/// stream_mgr::init_embedded_stream_mgr(&stream_mgr_settings, &mut device_settings, files)?;
+/// ```
+#[macro_export]
+macro_rules! embed_files {
+    ($dir:literal => [$($file:literal),* $(,)?]) => {{
+        let mut files = ::std::collections::HashMap::<&'static str, &'static [u8]>::new();
+        $(
+        files.insert($file, include_bytes!(concat!($dir, "/", $file)));
+        )*
+        files
+    }};
+}
+
 #[derive(Debug, Copy, Clone)]
 /// Description of a MIDI event
 pub enum AkMIDIEvent {
@@ -4,10 +4,13 @@
 
 #[cfg(not(wwrelease))]
 pub use crate::bindings::root::AkCommSettings;
+use crate::bindings::root::GetDefaultSpatialAudioInitSettings;
 #[cfg(not(wwrelease))]
 use crate::bindings::root::AK::Comm;
 use crate::bindings::root::AK::{MemoryMgr, MusicEngine, SoundEngine, StreamMgr};
-pub use crate::bindings::root::{AkMemSettings, AkMusicSettings, AkStreamMgrSettings};
+pub use crate::bindings::root::{
+    AkMemSettings, AkMusicSettings, AkSpatialAudioInitSettings, AkStreamMgrSettings,
+};
 use crate::to_os_char;
 use crate::OsChar;
 use log::error;
@@ -35,6 +38,20 @@ impl Default for AkMusicSettings {
     }
 }
 
+impl Default for AkSpatialAudioInitSettings {
+    /// Get the default values of the initialization settings of Spatial Audio.
+    ///
+    /// *See also*
+    /// > - [spatial_audio::init](crate::spatial_audio::init)
+    fn default() -> Self {
+        unsafe {
+            let mut ss: AkSpatialAudioInitSettings = std::mem::zeroed();
+            GetDefaultSpatialAudioInitSettings(&mut ss);
+            ss
+        }
+    }
+}
+
 impl Default for AkStreamMgrSettings {
     /// Get the default values for the Stream Manager's settings.
     ///
@@ -7,9 +7,19 @@ use crate::{
     settings::{AkInitSettings, AkPlatformInitSettings},
     *,
 };
+use ::std::collections::HashMap;
 use ::std::convert::TryInto;
 use ::std::ffi::CStr;
-use ::std::fmt::Debug;
+use ::std::fmt::{Debug, Display, Formatter};
+use ::std::sync::{Arc, Mutex};
+
+/// In-memory external sources of the events started by [PostEvent::post], kept alive until their
+/// event ends.
+static EXTERNAL_SOURCE_BUFFERS: Mutex<Vec<(AkPlayingID, Vec<Arc<[u8]>>)>> = Mutex::new(Vec::new());
+
+/// Number of times each bank was loaded through the functions of this module, and not unloaded
+/// yet.
+static LOADED_BANKS: Mutex<Option<HashMap<AkBankID, usize>>> = Mutex::new(None);
 
 macro_rules! link_static_plugin {
     ($feature:ident) => {
@@ -132,6 +142,9 @@ pub fn term() {
     unsafe {
         Term();
     }
+    crate::bank::forget_all();
+    LOADED_BANKS.lock().unwrap().take();
+    EXTERNAL_SOURCE_BUFFERS.lock().unwrap().clear();
 }
 
 /// Processes all commands in the sound engine's command queue.
@@ -167,7 +180,9 @@ pub fn render_audio(allow_sync_render: bool) -> Result<(), AkResult> {
 /// - [register_game_obj]
 /// - [unregister_game_obj]
 pub fn unregister_all_game_obj() -> Result<(), AkResult> {
-    ak_call_result![UnregisterAllGameObj()]
+    ak_call_result![UnregisterAllGameObj()]?;
+    crate::spatial_audio::untrack(None);
+    Ok(())
 }
 
 /// Unregisters a game object.
@@ -187,7 +202,9 @@ pub fn unregister_all_game_obj() -> Result<(), AkResult> {
 /// > - [register_game_obj]
 /// > - [unregister_all_game_obj]
 pub fn unregister_game_obj(game_object_id: AkGameObjectID) -> Result<(), AkResult> {
-    ak_call_result![UnregisterGameObj(game_object_id)]
+    ak_call_result![UnregisterGameObj(game_object_id)]?;
+    crate::spatial_audio::untrack(Some(game_object_id));
+    Ok(())
 }
 
 /// Registers a game object.
@@ -239,6 +256,10 @@ pub fn register_named_game_obj<T: AsRef<str>>(
 ///
 /// *Warning* `position`'s orientation vectors must be normalized.
 ///
+/// *Remark* When the automatic room assignment of Spatial Audio is enabled, the new position is
+/// also used to find the room the game object is in; see
+/// [spatial_audio::enable_automatic_room_assignment](crate::spatial_audio::enable_automatic_room_assignment).
+///
 /// *Return*
 /// > - [AK_Success](AkResult::AK_Success) when successful
 /// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter) if parameters are not valid.
@@ -246,7 +267,10 @@ pub fn set_position<T: Into<AkSoundPosition>>(
     game_object_id: AkGameObjectID,
     position: T,
 ) -> Result<(), AkResult> {
-    ak_call_result![SetPosition(game_object_id, &position.into())]
+    let position = position.into();
+    ak_call_result![SetPosition(game_object_id, &position)]?;
+    crate::spatial_audio::track_position(game_object_id, &position.position);
+    Ok(())
 }
 
 /// Sets the default set of associated listeners for game objects that have not explicitly overridden their listener sets. Upon registration, all game objects reference the default listener set, until
@@ -340,6 +364,20 @@ pub fn stop_all(game_object_id: Option<AkGameObjectID>) {
     }
 }
 
+/// Universal converter from string to ID for the sound engine.
+///
+/// This function will hash the name based on a algorithm ( provided at : /AK/Tools/Common/AkFNVHash.h )
+///
+/// *Note* This function does return a [AkUInt32], which is totally compatible with [AkUniqueID],
+/// [AkStateGroupID], [AkStateID], [AkSwitchGroupID], [AkSwitchStateID], [AkRtpcID], and so on...
+///
+/// *See also* [PostEvent]
+pub fn get_id_from_string<T: AsRef<str>>(name: T) -> AkUInt32 {
+    with_cstring![name.as_ref() => cname {
+        unsafe { GetIDFromString1(cname.as_ptr()) }
+    }]
+}
+
 /// Load a bank synchronously (by Unicode string).
 ///
 /// The bank name is passed to the Stream Manager.
@@ -349,7 +387,7 @@ pub fn stop_all(game_object_id: Option<AkGameObjectID>) {
 /// The function returns when the request has been completely processed.
 ///
 /// *Return*
-/// The bank ID (see [get_id_from_string]). You may use this ID with [unload_bank].
+/// The bank ID (see [get_id_from_string]). You may use this ID with [unload_bank_by_id].
 /// > - [AK_Success](AkResult::AK_Success): Load or unload successful.
 /// > - [AK_InsufficientMemory](AkResult::AK_InsufficientMemory): Insufficient memory to store bank data.
 /// > - [AK_BankReadError](AkResult::AK_BankReadError): I/O error.
@@ -382,27 +420,1056 @@ pub fn stop_all(game_object_id: Option<AkGameObjectID>) {
 pub fn load_bank_by_name<T: AsRef<str>>(name: T) -> Result<AkBankID, AkResult> {
     let mut bank_id = 0;
     with_cstring![name.as_ref() => cname {
-        ak_call_result![LoadBank1(cname.as_ptr(), &mut bank_id) => bank_id]
+        record_load(ak_call_result![LoadBank1(cname.as_ptr(), &mut bank_id) => bank_id])
     }]
 }
 
-#[derive(Debug, Copy, Clone)]
+/// Load a bank synchronously (by ID).
+///
+/// The bank ID is passed to the Stream Manager: the Low-Level I/O must be able to resolve it to a
+/// file, for instance when banks are packaged in a file package.
+///
+/// *Return* The bank ID if successful, or the same errors as [load_bank_by_name].
+///
+/// *See also*
+/// > - [load_bank_by_name]
+/// > - [unload_bank_by_id]
+pub fn load_bank_by_id(bank_id: AkBankID) -> Result<AkBankID, AkResult> {
+    record_load(ak_call_result![LoadBank2(bank_id) => bank_id])
+}
+
+/// A SoundBank in memory, aligned on [AK_BANK_PLATFORM_DATA_ALIGNMENT] so that it can be loaded
+/// in-place with [Bank::load_memory_view](crate::bank::Bank::load_memory_view), which keeps it alive
+/// until the bank is unloaded.
+///
+/// It either borrows a `'static` buffer, such as one embedded with
+/// [include_bank!](crate::include_bank), or owns a buffer allocated at runtime, for instance to
+/// load banks extracted from an archive, a save or a mod file.
+pub struct BankMemory(BankMemoryBuffer);
+
+enum BankMemoryBuffer {
+    Static(&'static [u8]),
+    Owned(::std::ptr::NonNull<u8>, usize),
+}
+
+// The owned buffer is never aliased mutably once built
+unsafe impl Send for BankMemory {}
+unsafe impl Sync for BankMemory {}
+
+impl BankMemory {
+    fn layout(len: usize) -> ::std::alloc::Layout {
+        ::std::alloc::Layout::from_size_align(len.max(1), AK_BANK_PLATFORM_DATA_ALIGNMENT as usize)
+            .expect("bank should fit in memory")
+    }
+
+    /// Allocate an aligned buffer of `len` bytes and fill it with `fill`.
+    fn new_with<E, F: FnOnce(&mut [u8]) -> Result<(), E>>(len: usize, fill: F) -> Result<Self, E> {
+        let layout = Self::layout(len);
+        let ptr = match ::std::ptr::NonNull::new(unsafe { ::std::alloc::alloc_zeroed(layout) }) {
+            Some(ptr) => ptr,
+            None => ::std::alloc::handle_alloc_error(layout),
+        };
+        // Freed on error
+        let memory = Self(BankMemoryBuffer::Owned(ptr, len));
+        fill(unsafe { ::std::slice::from_raw_parts_mut(ptr.as_ptr(), len) })?;
+        Ok(memory)
+    }
+
+    /// Copy `bank` into a new aligned buffer.
+    pub fn from_bytes(bank: &[u8]) -> Self {
+        match Self::new_with::<(), _>(bank.len(), |buffer| {
+            buffer.copy_from_slice(bank);
+            Ok(())
+        }) {
+            Ok(memory) => memory,
+            Err(()) => unreachable!(),
+        }
+    }
+
+    /// Read the `len` bytes of a bank from `reader` straight into a new aligned buffer.
+    ///
+    /// *Return* The error of [Read::read_exact](::std::io::Read::read_exact) if the bank couldn't
+    /// be read.
+    pub fn read_from<R: ::std::io::Read>(mut reader: R, len: usize) -> ::std::io::Result<Self> {
+        Self::new_with(len, |buffer| reader.read_exact(buffer))
+    }
+}
+
+impl ::std::ops::Deref for BankMemory {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self.0 {
+            BankMemoryBuffer::Static(bank) => bank,
+            BankMemoryBuffer::Owned(ptr, len) => unsafe {
+                ::std::slice::from_raw_parts(ptr.as_ptr(), len)
+            },
+        }
+    }
+}
+
+impl Drop for BankMemory {
+    fn drop(&mut self) {
+        if let BankMemoryBuffer::Owned(ptr, len) = self.0 {
+            unsafe { ::std::alloc::dealloc(ptr.as_ptr(), Self::layout(len)) };
+        }
+    }
+}
+
+impl Debug for BankMemory {
+    fn fmt(&self, f: &mut Formatter<'_>) -> ::std::fmt::Result {
+        f.debug_struct("BankMemory")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+/// Borrows `bank` without copying it; it must be aligned on [AK_BANK_PLATFORM_DATA_ALIGNMENT] to
+/// be loaded.
+impl From<&'static [u8]> for BankMemory {
+    fn from(bank: &'static [u8]) -> Self {
+        Self(BankMemoryBuffer::Static(bank))
+    }
+}
+
+/// Copies `bank` into a new aligned buffer.
+impl From<Vec<u8>> for BankMemory {
+    fn from(bank: Vec<u8>) -> Self {
+        Self::from_bytes(&bank)
+    }
+}
+
+/// Copies `bank` into a new aligned buffer.
+impl From<Box<[u8]>> for BankMemory {
+    fn from(bank: Box<[u8]>) -> Self {
+        Self::from_bytes(&bank)
+    }
+}
+
+/// Load a bank synchronously, from an in-memory buffer, in-place.
+///
+/// The bank is used directly from `bank`: it must stay valid as long as the bank is loaded, which
+/// is why only `'static` buffers are accepted. Use [include_bank!](crate::include_bank) to embed a
+/// bank in the executable with the right alignment, a [BankMemory] loaded through
+/// [Bank::load_memory_view](crate::bank::Bank::load_memory_view) for buffers read at runtime, or
+/// [load_bank_memory_copy] to let the sound engine copy the bank.
+///
+/// *Return*
+/// The bank ID, which is stored in the first few bytes of the bank file. You may use this ID with [unload_bank_by_id].
+/// > - [AK_Success](AkResult::AK_Success): Load or unload successful.
+/// > - [AK_InsufficientMemory](AkResult::AK_InsufficientMemory): Insufficient memory to store bank data.
+/// > - [AK_BankReadError](AkResult::AK_BankReadError): I/O error.
+/// > - [AK_WrongBankVersion](AkResult::AK_WrongBankVersion): Invalid bank version: make sure the version of Wwise that you used to generate the SoundBanks matches that of the SDK you are currently using.
+/// > - [AK_InvalidFile](AkResult::AK_InvalidFile): File specified could not be opened.
+/// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter): Invalid parameter, `bank` is not aligned on [AK_BANK_PLATFORM_DATA_ALIGNMENT] bytes.
+/// > - [AK_Fail](AkResult::AK_Fail): Load or unload failed for any other reason. (Most likely small allocation failure)
+///
+/// *Remarks*
+/// > - The initialization bank must be loaded first.
+/// > - All SoundBanks subsequently loaded must come from the same Wwise project as the
+///   initialization bank. If you need to load SoundBanks from a different project, you
+///   must first unload ALL banks, including the initialization bank, then load the
+///   initialization bank from the other project, and finally load banks from that project.
+/// > - Codecs and plug-ins must be registered before loading banks that use them.
+///
+/// *See also*
+/// > - [load_bank_memory_copy]
+/// > - [include_bank!](crate::include_bank)
+/// > - [unload_bank_by_id]
+pub fn load_bank_memory_view(bank: &'static [u8]) -> Result<AkBankID, AkResult> {
+    unsafe { load_bank_memory_view_unchecked(bank) }
+}
+
+/// Same as [load_bank_memory_view], for a buffer of any lifetime.
+///
+/// # Safety
+/// `bank` must stay valid until the bank is unloaded.
+pub(crate) unsafe fn load_bank_memory_view_unchecked(bank: &[u8]) -> Result<AkBankID, AkResult> {
+    if bank.as_ptr() as usize % AK_BANK_PLATFORM_DATA_ALIGNMENT as usize != 0 {
+        return Err(AkResult::AK_InvalidParameter);
+    }
+
+    let mut bank_id = 0;
+    record_load(ak_call_result![LoadBankMemoryView(
+        bank.as_ptr() as *const _,
+        bank.len() as AkUInt32,
+        &mut bank_id
+    ) => bank_id])
+}
+
+/// Load a bank synchronously, from an in-memory buffer, copying it.
+///
+/// The sound engine allocates memory for the bank and copies `bank` into it: `bank` can be
+/// dropped as soon as this function returns, and doesn't need any particular alignment.
+///
+/// *Return*
+/// The bank ID, which is stored in the first few bytes of the bank file. You may use this ID with [unload_bank_by_id].
+/// > - [AK_Success](AkResult::AK_Success): Load or unload successful.
+/// > - [AK_InsufficientMemory](AkResult::AK_InsufficientMemory): Insufficient memory to store bank data.
+/// > - [AK_BankReadError](AkResult::AK_BankReadError): I/O error.
+/// > - [AK_WrongBankVersion](AkResult::AK_WrongBankVersion): Invalid bank version: make sure the version of Wwise that you used to generate the SoundBanks matches that of the SDK you are currently using.
+/// > - [AK_InvalidFile](AkResult::AK_InvalidFile): File specified could not be opened.
+/// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter): Invalid parameter.
+/// > - [AK_Fail](AkResult::AK_Fail): Load or unload failed for any other reason. (Most likely small allocation failure)
+///
+/// *See also*
+/// > - [load_bank_memory_view]
+/// > - [unload_bank_by_id]
+pub fn load_bank_memory_copy(bank: &[u8]) -> Result<AkBankID, AkResult> {
+    let mut bank_id = 0;
+    record_load(ak_call_result![LoadBankMemoryCopy(
+        bank.as_ptr() as *const _,
+        bank.len() as AkUInt32,
+        &mut bank_id
+    ) => bank_id])
+}
+
+/// Whether the bank `bank_id` is loaded, by any of the bank loading functions of this module or
+/// through a [Bank](crate::bank::Bank) handle.
+///
+/// *Remark* Banks loaded by the sound engine itself, for instance when preparing events, are not
+/// accounted for.
+pub fn is_bank_loaded(bank_id: AkBankID) -> bool {
+    LOADED_BANKS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .map_or(false, |banks| banks.contains_key(&bank_id))
+}
+
+fn record_load(result: Result<AkBankID, AkResult>) -> Result<AkBankID, AkResult> {
+    if let Ok(bank_id) = result {
+        *LOADED_BANKS
+            .lock()
+            .unwrap()
+            .get_or_insert_with(HashMap::new)
+            .entry(bank_id)
+            .or_default() += 1;
+    }
+    result
+}
+
+/// Wrap the callback of an asynchronous bank load to record it once loaded.
+fn record_load_async<F>(callback: F) -> impl FnOnce(BankCallbackResult) + Send + 'static
+where
+    F: FnOnce(BankCallbackResult) + Send + 'static,
+{
+    move |result| callback(record_load(result))
+}
+
+/// The sound engine reports unloading a bank that is not loaded as a success.
+fn record_unload(bank_id: AkBankID) {
+    if let Some(banks) = LOADED_BANKS.lock().unwrap().as_mut() {
+        if let Some(count) = banks.get_mut(&bank_id) {
+            *count -= 1;
+            if *count == 0 {
+                banks.remove(&bank_id);
+            }
+        }
+    }
+}
+
+/// Result of an asynchronous bank operation: the bank ID if successful.
+pub type BankCallbackResult = Result<AkBankID, AkResult>;
+
+struct BankCallbackCookie {
+    callback: Box<dyn FnOnce(BankCallbackResult) + Send>,
+    _keep_alive: Option<Vec<u8>>,
+}
+
+impl BankCallbackCookie {
+    fn into_raw<F>(callback: F, keep_alive: Option<Vec<u8>>) -> *mut ::std::os::raw::c_void
+    where
+        F: FnOnce(BankCallbackResult) + Send + 'static,
+    {
+        Box::into_raw(Box::new(Self {
+            callback: Box::new(callback),
+            _keep_alive: keep_alive,
+        })) as *mut _
+    }
+
+    /// Call this after a failed request: Wwise will never call back.
+    unsafe fn drop_raw(cookie: *mut ::std::os::raw::c_void) {
+        drop(Box::from_raw(cookie as *mut Self));
+    }
+
+    unsafe extern "C" fn call(
+        bank_id: AkUInt32,
+        _in_memory_bank_ptr: *const ::std::os::raw::c_void,
+        load_result: AKRESULT,
+        cookie: *mut ::std::os::raw::c_void,
+    ) {
+        let cookie = Box::from_raw(cookie as *mut Self);
+        (cookie.callback)(match load_result {
+            AkResult::AK_Success => Ok(bank_id),
+            error_code => Err(error_code),
+        });
+    }
+}
+
+macro_rules! async_bank_call {
+    ($cookie:ident => $the_call:expr) => {{
+        let result = ak_call_result![$the_call];
+        if result.is_err() {
+            unsafe { BankCallbackCookie::drop_raw($cookie) };
+        }
+        result
+    }};
+}
+
+/// Load a bank asynchronously (by Unicode string).
+///
+/// The bank name is passed to the Stream Manager.
+///
+/// A bank load request will be posted to the Bank Manager consumer thread; the function returns
+/// immediately, and `callback` is called from the Bank Manager thread once the bank is loaded, or
+/// failed to load.
+///
+/// *Return* The bank ID (see [get_id_from_string]) if the request was posted, or an error if it
+/// could not be (in which case `callback` is never called). See [load_bank_by_name] for the
+/// errors given to `callback`.
+///
+/// *Remarks*
+/// > - The sound engine must be initialized with
+/// [use_sound_bank_mgr_thread](crate::settings::AkInitSettings::use_sound_bank_mgr_thread).
+/// > - `callback` must not take long to return, as it blocks the Bank Manager thread.
+///
+/// *See also*
+/// > - [load_bank_by_name]
+/// > - [Bank::load_async](crate::bank::Bank::load_async)
+pub fn load_bank_by_name_async<T, F>(name: T, callback: F) -> Result<AkBankID, AkResult>
+where
+    T: AsRef<str>,
+    F: FnOnce(BankCallbackResult) + Send + 'static,
+{
+    let mut bank_id = 0;
+    let cookie = BankCallbackCookie::into_raw(record_load_async(callback), None);
+    with_cstring![name.as_ref() => cname {
+        async_bank_call![cookie => LoadBank4(
+            cname.as_ptr(),
+            Some(BankCallbackCookie::call),
+            cookie,
+            &mut bank_id
+        )]
+    }]?;
+    Ok(bank_id)
+}
+
+/// Load a bank asynchronously (by ID).
+///
+/// Same as [load_bank_by_name_async], with the bank ID passed to the Stream Manager.
+///
+/// *See also*
+/// > - [load_bank_by_id]
+pub fn load_bank_by_id_async<F>(bank_id: AkBankID, callback: F) -> Result<(), AkResult>
+where
+    F: FnOnce(BankCallbackResult) + Send + 'static,
+{
+    let cookie = BankCallbackCookie::into_raw(record_load_async(callback), None);
+    async_bank_call![cookie => LoadBank5(bank_id, Some(BankCallbackCookie::call), cookie)]
+}
+
+/// Load a bank asynchronously, from an in-memory buffer, in-place.
+///
+/// Same as [load_bank_memory_view], except that `callback` is called from the Bank Manager thread
+/// once the bank is loaded, or failed to load.
+///
+/// *Return* The bank ID if the request was posted.
+///
+/// *See also* [load_bank_by_name_async]
+pub fn load_bank_memory_view_async<F>(
+    bank: &'static [u8],
+    callback: F,
+) -> Result<AkBankID, AkResult>
+where
+    F: FnOnce(BankCallbackResult) + Send + 'static,
+{
+    unsafe { load_bank_memory_view_async_unchecked(bank, callback) }
+}
+
+/// Same as [load_bank_memory_view_async], for a buffer of any lifetime.
+///
+/// # Safety
+/// `bank` must stay valid until the bank is unloaded, or `callback` is called with an error.
+pub(crate) unsafe fn load_bank_memory_view_async_unchecked<F>(
+    bank: &[u8],
+    callback: F,
+) -> Result<AkBankID, AkResult>
+where
+    F: FnOnce(BankCallbackResult) + Send + 'static,
+{
+    if bank.as_ptr() as usize % AK_BANK_PLATFORM_DATA_ALIGNMENT as usize != 0 {
+        return Err(AkResult::AK_InvalidParameter);
+    }
+
+    let mut bank_id = 0;
+    let cookie = BankCallbackCookie::into_raw(record_load_async(callback), None);
+    async_bank_call![cookie => LoadBankMemoryView1(
+        bank.as_ptr() as *const _,
+        bank.len() as AkUInt32,
+        Some(BankCallbackCookie::call),
+        cookie,
+        &mut bank_id
+    )]?;
+    Ok(bank_id)
+}
+
+/// Load a bank asynchronously, from an in-memory buffer, copying it.
+///
+/// Same as [load_bank_memory_copy], except that `callback` is called from the Bank Manager thread
+/// once the bank is loaded, or failed to load. `bank` is kept alive until then.
+///
+/// *Return* The bank ID if the request was posted.
+///
+/// *See also* [load_bank_by_name_async]
+pub fn load_bank_memory_copy_async<F>(bank: Vec<u8>, callback: F) -> Result<AkBankID, AkResult>
+where
+    F: FnOnce(BankCallbackResult) + Send + 'static,
+{
+    let mut bank_id = 0;
+    let (ptr, len) = (bank.as_ptr(), bank.len());
+    let cookie = BankCallbackCookie::into_raw(record_load_async(callback), Some(bank));
+    async_bank_call![cookie => LoadBankMemoryCopy1(
+        ptr as *const _,
+        len as AkUInt32,
+        Some(BankCallbackCookie::call),
+        cookie,
+        &mut bank_id
+    )]?;
+    Ok(bank_id)
+}
+
+/// Unload a bank synchronously, by name.
+///
+/// `memory_view` must be the buffer the bank was loaded from with [load_bank_memory_view], or
+/// [None] if the bank was loaded another way.
+///
+/// *Return* [AK_Success](AkResult::AK_Success) if successful, [AK_Fail](AkResult::AK_Fail) otherwise.
+/// [AK_Success](AkResult::AK_Success) is returned when the bank was not loaded.
+///
+/// *Remarks*
+/// > - If you provided a pool memory ID when loading this bank, it is returned as well.
+/// Otherwise, the function returns [AK_DEFAULT_POOL_ID].
+/// > - The sound engine internally calls [get_id_from_string] to retrieve the bank ID, then it
+/// calls the synchronous version of [unload_bank_by_id].
+/// > - Banks loaded through a [Bank](crate::bank::Bank) handle should be unloaded by dropping the
+/// handle instead.
+///
+/// *See also*
+/// > - [unload_bank_by_id]
+/// > - [clear_banks]
+/// > - [load_bank_by_name]
+pub fn unload_bank_by_name<T: AsRef<str>>(
+    name: T,
+    memory_view: Option<&[u8]>,
+) -> Result<(), AkResult> {
+    let memory_ptr = memory_view.map_or(::std::ptr::null(), |bank| bank.as_ptr() as *const _);
+    with_cstring![name.as_ref() => cname {
+        ak_call_result![UnloadBank1(cname.as_ptr(), memory_ptr)]
+    }]?;
+    record_unload(crate::bank::bank_id_from_name(name.as_ref()));
+    Ok(())
+}
+
+/// Unload a bank synchronously, by ID.
+///
+/// `memory_view` must be the buffer the bank was loaded from with [load_bank_memory_view], or
+/// [None] if the bank was loaded another way.
+///
+/// *Return* [AK_Success](AkResult::AK_Success) if successful, [AK_Fail](AkResult::AK_Fail) otherwise.
+/// [AK_Success](AkResult::AK_Success) is returned when the bank was not loaded.
+///
+/// *Remark* Banks loaded through a [Bank](crate::bank::Bank) handle should be unloaded by dropping
+/// the handle instead.
+///
+/// *See also*
+/// > - [unload_bank_by_name]
+/// > - [clear_banks]
+pub fn unload_bank_by_id(bank_id: AkBankID, memory_view: Option<&[u8]>) -> Result<(), AkResult> {
+    let memory_ptr = memory_view.map_or(::std::ptr::null(), |bank| bank.as_ptr() as *const _);
+    ak_call_result![UnloadBank2(bank_id, memory_ptr)]?;
+    record_unload(bank_id);
+    Ok(())
+}
+
+/// Unload all currently loaded banks. It also internally calls [clear_prepared_events].
+///
+/// All the [Bank](crate::bank::Bank) handles still alive become inert: dropping them doesn't
+/// unload anything anymore.
+///
+/// *Return*
+/// > - [AK_Success](AkResult::AK_Success) if successful
+/// > - [AK_Fail](AkResult::AK_Fail) if the sound engine was not correctly initialized or if there is not enough memory to handle the command
+///
+/// *See also*
+/// > - [unload_bank_by_name]
+/// > - [unload_bank_by_id]
+/// > - [load_bank_by_name]
+pub fn clear_banks() -> Result<(), AkResult> {
+    crate::bank::forget_all();
+    LOADED_BANKS.lock().unwrap().take();
+    ak_call_result![ClearBanks()]
+}
+
+/// Names or IDs of the objects to prepare, all of the same [AkID] variant.
+enum PreparedIDs {
+    Names(Vec<::std::ffi::CString>, Vec<*const ::std::os::raw::c_char>),
+    IDs(Vec<AkUniqueID>),
+}
+
+impl PreparedIDs {
+    /// Panics if `ids` are not all of the same variant.
+    fn new<'a, T: Into<AkID<'a>> + Copy>(ids: &[T]) -> Self {
+        let ids: Vec<AkID> = ids.iter().map(|&id| id.into()).collect();
+        match ids.first() {
+            Some(AkID::Name(_)) => {
+                let names: Vec<_> = ids
+                    .iter()
+                    .map(|id| match id {
+                        AkID::Name(name) => ::std::ffi::CString::new(*name)
+                            .expect("text shouldn't contain null bytes"),
+                        AkID::ID(_) => panic!("All IDs to prepare should be of the same variant"),
+                    })
+                    .collect();
+                let ptrs = names.iter().map(|name| name.as_ptr()).collect();
+                Self::Names(names, ptrs)
+            }
+            _ => Self::IDs(
+                ids.iter()
+                    .map(|id| match id {
+                        AkID::ID(id) => *id,
+                        AkID::Name(_) => panic!("All IDs to prepare should be of the same variant"),
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn len(&self) -> AkUInt32 {
+        match self {
+            Self::Names(names, _) => names.len() as AkUInt32,
+            Self::IDs(ids) => ids.len() as AkUInt32,
+        }
+    }
+}
+
+/// Prepare or unprepare events synchronously.
+///
+/// Preparing an event loads the media it needs (and the structure of the objects it references, if
+/// they are not already loaded) from the banks and loose media files in which they are, without
+/// having to load whole banks. Events are reference-counted: an event prepared twice must be
+/// unprepared twice before its media is released.
+///
+/// `preparation_type` [Preparation_Load](PreparationType::Preparation_Load) prepares the events,
+/// [Preparation_Unload](PreparationType::Preparation_Unload) unprepares them.
+///
+/// *Return*
+/// > - [AK_Success](AkResult::AK_Success): Prepare/un-prepare successful.
+/// > - [AK_IDNotFound](AkResult::AK_IDNotFound): At least one of the event/game sync identifiers passed to PrepareEvent() does not exist.
+/// > - [AK_InsufficientMemory](AkResult::AK_InsufficientMemory): Insufficient memory to store bank data.
+/// > - [AK_BankReadError](AkResult::AK_BankReadError): I/O error.
+/// > - [AK_WrongBankVersion](AkResult::AK_WrongBankVersion): Invalid bank version: make sure the version of Wwise that you used to generate the SoundBanks matches that of the SDK you are currently using.
+/// > - [AK_InvalidFile](AkResult::AK_InvalidFile): File specified could not be opened.
+/// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter): Invalid parameter, invalid memory alignment.
+/// > - [AK_Fail](AkResult::AK_Fail): Load or unload failed for any other reason. (Most likely small allocation failure)
+///
+/// *Remarks*
+/// > - The structure of the events must have been loaded beforehand, typically with a bank
+/// generated with its structure only (see [prepare_bank] and
+/// [AkBankContent_StructureOnly](AkBankContent::AkBankContent_StructureOnly)).
+/// > - With [enable_game_sync_preparation](crate::settings::AkInitSettings::enable_game_sync_preparation),
+/// only the media needed by the game syncs prepared with [prepare_game_syncs] is loaded.
+/// Otherwise, the media of all the switches and states used by the events is loaded.
+///
+/// Panics if `events` are not all of the same variant.
+///
+/// *See also*
+/// > - [prepare_event_async]
+/// > - [clear_prepared_events]
+/// > - [prepare_game_syncs]
+pub fn prepare_event<'a, T: Into<AkID<'a>> + Copy>(
+    preparation_type: PreparationType,
+    events: &[T],
+) -> Result<(), AkResult> {
+    let mut events = PreparedIDs::new(events);
+    let num_events = events.len();
+    match &mut events {
+        PreparedIDs::Names(_, ptrs) => {
+            ak_call_result![PrepareEvent1(
+                preparation_type,
+                ptrs.as_mut_ptr(),
+                num_events
+            )]
+        }
+        PreparedIDs::IDs(ids) => {
+            ak_call_result![PrepareEvent2(
+                preparation_type,
+                ids.as_mut_ptr(),
+                num_events
+            )]
+        }
+    }
+}
+
+/// Prepare or unprepare events asynchronously.
+///
+/// Same as [prepare_event], except that the request is posted to the Bank Manager thread and
+/// `callback` is called from there once it has been processed.
+///
+/// *Return* [AK_Success](AkResult::AK_Success) if the request was posted, or an error if it could
+/// not be (in which case `callback` is never called). See [prepare_event] for the errors given to
+/// `callback`.
+///
+/// Panics if `events` are not all of the same variant.
+pub fn prepare_event_async<'a, T, F>(
+    preparation_type: PreparationType,
+    events: &[T],
+    callback: F,
+) -> Result<(), AkResult>
+where
+    T: Into<AkID<'a>> + Copy,
+    F: FnOnce(Result<(), AkResult>) + Send + 'static,
+{
+    let mut events = PreparedIDs::new(events);
+    let num_events = events.len();
+    let cookie = BankCallbackCookie::into_raw(move |result| callback(result.map(|_| ())), None);
+    match &mut events {
+        PreparedIDs::Names(_, ptrs) => async_bank_call![cookie => PrepareEvent4(
+            preparation_type,
+            ptrs.as_mut_ptr(),
+            num_events,
+            Some(BankCallbackCookie::call),
+            cookie
+        )],
+        PreparedIDs::IDs(ids) => async_bank_call![cookie => PrepareEvent5(
+            preparation_type,
+            ids.as_mut_ptr(),
+            num_events,
+            Some(BankCallbackCookie::call),
+            cookie
+        )],
+    }
+}
+
+/// Prepare or unprepare game syncs synchronously.
+///
+/// Only has an effect if the sound engine was initialized with
+/// [enable_game_sync_preparation](crate::settings::AkInitSettings::enable_game_sync_preparation):
+/// the media of the events prepared with [prepare_event] is then only loaded for the switches or
+/// states prepared with this function. Game syncs are reference-counted like prepared events.
+///
+/// `game_sync_type` is [AkGroupType_Switch](AkGroupType::AkGroupType_Switch) or
+/// [AkGroupType_State](AkGroupType::AkGroupType_State), `group` is the Switch Group or State Group
+/// and `game_syncs` the switches or states in it to prepare.
+///
+/// *Return* Same as [prepare_event].
+///
+/// Panics if `group` and `game_syncs` are not all of the same variant.
+///
+/// *See also*
+/// > - [prepare_game_syncs_async]
+/// > - [prepare_event]
+pub fn prepare_game_syncs<'a, T: Into<AkID<'a>> + Copy>(
+    preparation_type: PreparationType,
+    game_sync_type: AkGroupType,
+    group: T,
+    game_syncs: &[T],
+) -> Result<(), AkResult> {
+    let mut game_syncs = PreparedIDs::new(game_syncs);
+    let num_game_syncs = game_syncs.len();
+    match (group.into(), &mut game_syncs) {
+        (AkID::Name(group), PreparedIDs::Names(_, ptrs)) => {
+            with_cstring![group => cgroup {
+                ak_call_result![PrepareGameSyncs1(
+                    preparation_type,
+                    game_sync_type,
+                    cgroup.as_ptr(),
+                    ptrs.as_mut_ptr(),
+                    num_game_syncs
+                )]
+            }]
+        }
+        (AkID::ID(group), PreparedIDs::IDs(ids)) => ak_call_result![PrepareGameSyncs2(
+            preparation_type,
+            game_sync_type,
+            group,
+            ids.as_mut_ptr(),
+            num_game_syncs
+        )],
+        _ => panic!("Args group and game_syncs should be of the same variant"),
+    }
+}
+
+/// Prepare or unprepare game syncs asynchronously.
+///
+/// Same as [prepare_game_syncs], except that the request is posted to the Bank Manager thread and
+/// `callback` is called from there once it has been processed.
+///
+/// *Return* Same as [prepare_event_async].
+///
+/// Panics if `group` and `game_syncs` are not all of the same variant.
+pub fn prepare_game_syncs_async<'a, T, F>(
+    preparation_type: PreparationType,
+    game_sync_type: AkGroupType,
+    group: T,
+    game_syncs: &[T],
+    callback: F,
+) -> Result<(), AkResult>
+where
+    T: Into<AkID<'a>> + Copy,
+    F: FnOnce(Result<(), AkResult>) + Send + 'static,
+{
+    let mut game_syncs = PreparedIDs::new(game_syncs);
+    let num_game_syncs = game_syncs.len();
+    match (group.into(), &mut game_syncs) {
+        (AkID::Name(group), PreparedIDs::Names(_, ptrs)) => {
+            let cookie =
+                BankCallbackCookie::into_raw(move |result| callback(result.map(|_| ())), None);
+            with_cstring![group => cgroup {
+                async_bank_call![cookie => PrepareGameSyncs4(
+                    preparation_type,
+                    game_sync_type,
+                    cgroup.as_ptr(),
+                    ptrs.as_mut_ptr(),
+                    num_game_syncs,
+                    Some(BankCallbackCookie::call),
+                    cookie
+                )]
+            }]
+        }
+        (AkID::ID(group), PreparedIDs::IDs(ids)) => {
+            let cookie =
+                BankCallbackCookie::into_raw(move |result| callback(result.map(|_| ())), None);
+            async_bank_call![cookie => PrepareGameSyncs5(
+                preparation_type,
+                game_sync_type,
+                group,
+                ids.as_mut_ptr(),
+                num_game_syncs,
+                Some(BankCallbackCookie::call),
+                cookie
+            )]
+        }
+        _ => panic!("Args group and game_syncs should be of the same variant"),
+    }
+}
+
+/// Prepare or unprepare a bank synchronously.
+///
+/// Preparing a bank loads its structure (and its media too with
+/// [AkBankContent_All](AkBankContent::AkBankContent_All)) without duplicating the media already
+/// loaded through [prepare_event] or another prepared bank. Prepared banks are reference-counted
+/// like prepared events.
+///
+/// `preparation_type` [Preparation_LoadAndDecode](PreparationType::Preparation_LoadAndDecode)
+/// also decodes the Vorbis media of the bank at load time, trading memory for CPU.
+///
+/// *Return* Same as [prepare_event].
+///
+/// *Remarks*
+/// > - Banks prepared by this function must be unprepared with
+/// [Preparation_Unload](PreparationType::Preparation_Unload); unloading them with
+/// [unload_bank_by_name] or [unload_bank_by_id] is not supported.
+/// > - A bank can't be both loaded with [load_bank_by_name] and prepared.
+///
+/// *See also*
+/// > - [prepare_bank_async]
+/// > - [prepare_event]
+pub fn prepare_bank<'a, T: Into<AkID<'a>>>(
+    preparation_type: PreparationType,
+    bank: T,
+    content: AkBankContent,
+) -> Result<(), AkResult> {
+    match bank.into() {
+        AkID::Name(name) => with_cstring![name => cname {
+            ak_call_result![PrepareBank1(preparation_type, cname.as_ptr(), content)]
+        }],
+        AkID::ID(id) => ak_call_result![PrepareBank2(preparation_type, id, content)],
+    }
+}
+
+/// Prepare or unprepare a bank asynchronously.
+///
+/// Same as [prepare_bank], except that the request is posted to the Bank Manager thread and
+/// `callback` is called from there with the bank ID once it has been processed.
+///
+/// *Return* Same as [prepare_event_async].
+pub fn prepare_bank_async<'a, T, F>(
+    preparation_type: PreparationType,
+    bank: T,
+    content: AkBankContent,
+    callback: F,
+) -> Result<(), AkResult>
+where
+    T: Into<AkID<'a>>,
+    F: FnOnce(BankCallbackResult) + Send + 'static,
+{
+    let cookie = BankCallbackCookie::into_raw(callback, None);
+    match bank.into() {
+        AkID::Name(name) => with_cstring![name => cname {
+            async_bank_call![cookie => PrepareBank4(
+                preparation_type,
+                cname.as_ptr(),
+                Some(BankCallbackCookie::call),
+                cookie,
+                content
+            )]
+        }],
+        AkID::ID(id) => async_bank_call![cookie => PrepareBank5(
+            preparation_type,
+            id,
+            Some(BankCallbackCookie::call),
+            cookie,
+            content
+        )],
+    }
+}
+
+/// Clear all the events prepared with [prepare_event], releasing the media they needed.
+///
+/// *Return*
+/// > - [AK_Success](AkResult::AK_Success) if successful
+/// > - [AK_Fail](AkResult::AK_Fail) if the sound engine was not correctly initialized or if there is not enough memory to handle the command
+///
+/// *See also* [prepare_event]
+pub fn clear_prepared_events() -> Result<(), AkResult> {
+    ak_call_result![ClearPreparedEvents()]
+}
+
+/// Pin the streamed media of an event in the stream cache, so that it plays without latency.
+///
+/// The first buffers of all the streamed files referenced by the event are read and kept in the
+/// stream cache, at `active_priority` while the event plays and `inactive_priority` otherwise.
+/// Use [get_buffer_status_for_pinned_event] to know when they are ready.
+///
+/// *Return* [AK_Success](AkResult::AK_Success) if successful, [AK_Fail](AkResult::AK_Fail)
+/// otherwise.
+///
+/// *Remarks*
+/// > - The stream cache must be enabled with
+/// [use_stream_cache](crate::settings::AkDeviceSettings::use_stream_cache), which
+/// [init_default_stream_mgr](crate::stream_mgr::init_default_stream_mgr) always does.
+/// > - The pinned data is limited by
+/// [max_cache_pinned_bytes](crate::settings::AkDeviceSettings::max_cache_pinned_bytes).
+/// > - Events must be unpinned as many times as they were pinned.
+///
+/// *See also*
+/// > - [unpin_event_in_stream_cache]
+/// > - [get_buffer_status_for_pinned_event]
+pub fn pin_event_in_stream_cache<'a, T: Into<AkID<'a>>>(
+    event: T,
+    active_priority: AkPriority,
+    inactive_priority: AkPriority,
+) -> Result<(), AkResult> {
+    match event.into() {
+        AkID::Name(name) => with_cstring![name => cname {
+            ak_call_result![PinEventInStreamCache2(
+                cname.as_ptr(),
+                active_priority,
+                inactive_priority
+            )]
+        }],
+        AkID::ID(id) => ak_call_result![PinEventInStreamCache(
+            id,
+            active_priority,
+            inactive_priority
+        )],
+    }
+}
+
+/// Release the streamed media of an event pinned with [pin_event_in_stream_cache].
+///
+/// *Return* [AK_Success](AkResult::AK_Success) if successful, [AK_Fail](AkResult::AK_Fail)
+/// otherwise.
+pub fn unpin_event_in_stream_cache<'a, T: Into<AkID<'a>>>(event: T) -> Result<(), AkResult> {
+    match event.into() {
+        AkID::Name(name) => with_cstring![name => cname {
+            ak_call_result![UnpinEventInStreamCache2(cname.as_ptr())]
+        }],
+        AkID::ID(id) => ak_call_result![UnpinEventInStreamCache(id)],
+    }
+}
+
+/// How much of the streamed media of a pinned event is in the stream cache.
+///
+/// *See also* [get_buffer_status_for_pinned_event]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PinnedEventBufferStatus {
+    /// Percentage of the pinned data that is buffered, from 0 to 100.
+    pub percent_buffered: AkReal32,
+    /// Whether the pinned data couldn't all be buffered because
+    /// [max_cache_pinned_bytes](crate::settings::AkDeviceSettings::max_cache_pinned_bytes) was
+    /// reached.
+    pub cache_pinned_memory_full: bool,
+}
+
+impl PinnedEventBufferStatus {
+    /// Whether all the pinned data of the event is buffered: the event can play without latency.
+    pub fn is_fully_buffered(&self) -> bool {
+        self.percent_buffered >= 100.
+    }
+}
+
+/// Get how much of the streamed media of an event pinned with [pin_event_in_stream_cache] is
+/// buffered.
+///
+/// *Return* The buffer status if successful, [AK_Fail](AkResult::AK_Fail) if the event is not
+/// pinned or another error occurred.
+pub fn get_buffer_status_for_pinned_event<'a, T: Into<AkID<'a>>>(
+    event: T,
+) -> Result<PinnedEventBufferStatus, AkResult> {
+    let mut percent_buffered = 0.;
+    let mut cache_pinned_memory_full = false;
+    match event.into() {
+        AkID::Name(name) => with_cstring![name => cname {
+            ak_call_result![GetBufferStatusForPinnedEvent2(
+                cname.as_ptr(),
+                &mut percent_buffered,
+                &mut cache_pinned_memory_full
+            )]
+        }],
+        AkID::ID(id) => ak_call_result![GetBufferStatusForPinnedEvent(
+            id,
+            &mut percent_buffered,
+            &mut cache_pinned_memory_full
+        )],
+    }?;
+
+    Ok(PinnedEventBufferStatus {
+        percent_buffered,
+        cache_pinned_memory_full,
+    })
+}
+
+/// Where an external source gets its data from.
+///
+/// *See also* [AkExternalSourceInfo]
+#[derive(Debug, Clone)]
+pub enum ExternalSourceData {
+    /// File path, resolved by the Low-Level I/O like a bank name would be.
+    File(String),
+    /// File ID, resolved by the Low-Level I/O.
+    FileID(AkFileID),
+    /// Bytes of a media file in memory. They are kept alive until the event that uses them has
+    /// finished playing.
+    Memory(Arc<[u8]>),
+}
+
+/// Information about an external source to attach to a [PostEvent].
+///
+/// An external source is a placeholder in the Wwise project, identified by its cookie, that is
+/// filled in at runtime with a media file; the codec the file was encoded with must be given (see
+/// the `AKCODECID_*` constants).
+///
+/// *See also* [PostEvent::add_external_source]
+#[derive(Debug, Clone)]
+pub struct AkExternalSourceInfo {
+    cookie: AkUInt32,
+    codec: AkCodecID,
+    data: ExternalSourceData,
+}
+
+impl AkExternalSourceInfo {
+    /// External source `cookie` (the name of the External Source in the Wwise project, or its ID)
+    /// playing `data`, encoded with `codec`.
+    pub fn new<'a, T: Into<AkID<'a>>>(
+        cookie: T,
+        codec: AkCodecID,
+        data: ExternalSourceData,
+    ) -> Self {
+        Self {
+            cookie: match cookie.into() {
+                AkID::Name(name) => get_id_from_string(name),
+                AkID::ID(id) => id,
+            },
+            codec,
+            data,
+        }
+    }
+
+    /// Shorthand for `new(cookie, codec, ExternalSourceData::File(path))`.
+    pub fn from_file<'a, T: Into<AkID<'a>>, P: AsRef<str>>(
+        cookie: T,
+        codec: AkCodecID,
+        path: P,
+    ) -> Self {
+        Self::new(
+            cookie,
+            codec,
+            ExternalSourceData::File(path.as_ref().to_string()),
+        )
+    }
+
+    /// Shorthand for `new(cookie, codec, ExternalSourceData::FileID(file_id))`.
+    pub fn from_file_id<'a, T: Into<AkID<'a>>>(
+        cookie: T,
+        codec: AkCodecID,
+        file_id: AkFileID,
+    ) -> Self {
+        Self::new(cookie, codec, ExternalSourceData::FileID(file_id))
+    }
+
+    /// Shorthand for `new(cookie, codec, ExternalSourceData::Memory(bytes.into()))`.
+    pub fn from_memory<'a, T: Into<AkID<'a>>, B: Into<Arc<[u8]>>>(
+        cookie: T,
+        codec: AkCodecID,
+        bytes: B,
+    ) -> Self {
+        Self::new(cookie, codec, ExternalSourceData::Memory(bytes.into()))
+    }
+
+    /// The cookie of the external source.
+    pub fn cookie(&self) -> AkUInt32 {
+        self.cookie
+    }
+
+    /// The codec of the media.
+    pub fn codec(&self) -> AkCodecID {
+        self.codec
+    }
+
+    /// Where the media comes from.
+    pub fn data(&self) -> &ExternalSourceData {
+        &self.data
+    }
+
+    /// Convert to the Wwise struct. `file_name` must outlive the returned value.
+    fn as_ak(&self, file_name: Option<&Vec<OsChar>>) -> bindings::root::AkExternalSourceInfo {
+        let mut info: bindings::root::AkExternalSourceInfo = unsafe { std::mem::zeroed() };
+        info.iExternalSrcCookie = self.cookie;
+        info.idCodec = self.codec;
+        match &self.data {
+            ExternalSourceData::File(_) => {
+                info.szFile = file_name.map_or(::std::ptr::null_mut(), |f| f.as_ptr() as *mut _)
+            }
+            ExternalSourceData::FileID(id) => info.idFile = *id,
+            ExternalSourceData::Memory(bytes) => {
+                info.pInMemory = bytes.as_ptr() as *mut _;
+                info.uiMemorySize = bytes.len() as AkUInt32;
+            }
+        }
+        info
+    }
+}
+
+#[derive(Debug, Clone)]
 /// Helper to post events to the sound engine.
 ///
 /// Use [PostEvent::post] to post your event to the sound engine.
 ///
 /// The callback function can be used to be noticed when markers are reached or when the event is finished.
 ///
-/// An array of Wave file sources can be provided to resolve External Sources triggered by the event.
+/// External sources can be attached with [add_external_source](Self::add_external_source) to
+/// resolve the External Sources triggered by the event.
 ///
-/// *Return* The playing ID of the event launched, or [AK_INVALID_PLAYING_ID] if posting the event failed
+/// *Return* A [PlayingHandle] over the event launched, or [AK_Fail](AkResult::AK_Fail) if posting the event failed
 ///
 /// *Remarks*
-/// > - If used, the array of external sources should contain the information for each external source triggered by the
+/// > - If used, the external sources should contain the information for each external source triggered by the
 /// event. When triggering an Event with multiple external sources, you need to differentiate each source
-/// by using the cookie property in the External Source in the Wwise project and in AkExternalSourceInfo.
+/// by using the cookie property in the External Source in the Wwise project and in [AkExternalSourceInfo].
 /// > - If an event triggers the playback of more than one external source, they must be named uniquely in the project
-/// (therefore have a unique cookie) in order to tell them apart when filling the AkExternalSourceInfo structures.
+/// (therefore have a unique cookie) in order to tell them apart when filling the [AkExternalSourceInfo] structures.
 ///
 /// *See also*
 /// > - [render_audio]
@@ -411,8 +1478,9 @@ pub struct PostEvent<'a> {
     game_obj_id: AkGameObjectID,
     event_id: AkID<'a>,
     flags: AkCallbackType,
-    // external_sources: Vec<...>  // TODO
+    external_sources: Vec<AkExternalSourceInfo>,
     playing_id: AkPlayingID,
+    bank_index: Option<Arc<crate::bank_index::BankIndex>>,
 }
 
 impl<'a> PostEvent<'a> {
@@ -422,8 +1490,9 @@ impl<'a> PostEvent<'a> {
             game_obj_id,
             event_id: event_id.into(),
             flags: AkCallbackType(0),
-            // external_sources: ...,
+            external_sources: Vec::new(),
             playing_id: AK_INVALID_PLAYING_ID,
+            bank_index: None,
         }
     }
 
@@ -443,6 +1512,17 @@ impl<'a> PostEvent<'a> {
         self
     }
 
+    /// Add an external source to resolve when posting the event.
+    ///
+    /// The data of [in-memory](ExternalSourceData::Memory) sources is kept alive until the event
+    /// ends, without needing a callback.
+    ///
+    /// *See also* [AkExternalSourceInfo]
+    pub fn add_external_source(&mut self, source: AkExternalSourceInfo) -> &mut Self {
+        self.external_sources.push(source);
+        self
+    }
+
     /// Advanced users only. Specify the playing ID to target with the event. Will Cause active
     /// actions in this event to target an existing Playing ID. Let it be [AK_INVALID_PLAYING_ID]
     /// or do not specify any for normal playback.
@@ -451,49 +1531,41 @@ impl<'a> PostEvent<'a> {
         self
     }
 
+    /// Make sure a bank containing the event is loaded before posting it, looking it up in `index`.
+    ///
+    /// [post](Self::post) and [post_with_callback](Self::post_with_callback) then load the bank
+    /// synchronously if needed; use [post_async](Self::post_async) to load it asynchronously.
+    ///
+    /// *See also* [BankIndex](crate::bank_index::BankIndex)
+    pub fn with_bank_index(&mut self, index: Arc<crate::bank_index::BankIndex>) -> &mut Self {
+        self.bank_index = Some(index);
+        self
+    }
+
     /// Posts the event to the sound engine.
-    pub fn post(&self) -> Result<AkPlayingID, AkResult> {
-        if let AkID::Name(name) = self.event_id {
-            let ak_playing_id = unsafe {
-                with_cstring![name => cname {
-                    PostEvent2(
-                        cname.as_ptr(),
-                        self.game_obj_id,
-                        self.flags.0 as u32,
-                        None,
-                        ::std::ptr::null_mut(),
-                        0,                      // TODO
-                        ::std::ptr::null_mut(), // TODO
-                        self.playing_id,
-                    )
-                }]
-            };
-            if ak_playing_id == AK_INVALID_PLAYING_ID {
-                Err(AkResult::AK_Fail)
-            } else {
-                Ok(ak_playing_id)
-            }
-        } else if let AkID::ID(id) = self.event_id {
-            let ak_playing_id = unsafe {
-                PostEvent(
-                    id,
-                    self.game_obj_id,
-                    self.flags.0 as u32,
-                    None,
-                    ::std::ptr::null_mut(),
-                    0,                      // TODO
-                    ::std::ptr::null_mut(), // TODO
-                    self.playing_id,
-                )
-            };
-            if ak_playing_id == AK_INVALID_PLAYING_ID {
-                Err(AkResult::AK_Fail)
-            } else {
-                Ok(ak_playing_id)
-            }
-        } else {
-            panic!("need at least an event ID or and an event name to post")
+    ///
+    /// *Return* A [PlayingHandle] to control the event instance that was started, or the error of
+    /// [BankIndex::load_banks_for](crate::bank_index::BankIndex::load_banks_for) if a
+    /// [bank index](Self::with_bank_index) is set and the bank of the event couldn't be loaded.
+    pub fn post(&self) -> Result<PlayingHandle, AkResult> {
+        if let Some(index) = &self.bank_index {
+            index.load_banks_for(self.event_id)?;
+        }
+
+        let (file_names, external_sources) = self.raw_external_sources();
+        let ak_playing_id =
+            unsafe { self.post_raw(self.flags, None, ::std::ptr::null_mut(), &external_sources) };
+        drop(file_names);
+
+        if ak_playing_id == AK_INVALID_PLAYING_ID {
+            return Err(AkResult::AK_Fail);
+        }
+
+        let buffers: Vec<Arc<[u8]>> = self.memory_buffers().cloned().collect();
+        if !buffers.is_empty() {
+            keep_external_source_buffers(ak_playing_id, buffers);
         }
+        Ok(self.handle(ak_playing_id))
     }
 
     /// Posts the event to the sound engine, calling `callback` according to [flags](Self::flags).
@@ -507,53 +1579,143 @@ impl<'a> PostEvent<'a> {
     ///
     /// This also means the closure or function must not be long to return, or audio might sutter as
     /// it prevents the audio thread from processing buffers.
-    pub fn post_with_callback<F>(&self, callback: F) -> Result<AkPlayingID, AkResult>
+    pub fn post_with_callback<F>(&self, mut callback: F) -> Result<PlayingHandle, AkResult>
+    where
+        F: FnMut(crate::AkCallbackInfo) + 'static,
+    {
+        if let Some(index) = &self.bank_index {
+            index.load_banks_for(self.event_id)?;
+        }
+
+        // the closure is dropped on AK_EndOfEvent, along with the in-memory external sources
+        let buffers: Vec<Arc<[u8]>> = self.memory_buffers().cloned().collect();
+        self.post_closure(move |info| {
+            let _keep_alive = &buffers;
+            callback(info)
+        })
+    }
+
+    /// Same as [post](Self::post), except that the bank of the event is loaded asynchronously if
+    /// a [bank index](Self::with_bank_index) is set.
+    ///
+    /// *See also* [BankIndex::load_banks_for_async](crate::bank_index::BankIndex::load_banks_for_async)
+    pub async fn post_async(&self) -> Result<PlayingHandle, AkResult> {
+        if let Some(index) = &self.bank_index {
+            index.load_banks_for_async(self.event_id).await?;
+        }
+        self.post()
+    }
+
+    fn post_closure<F>(&self, callback: F) -> Result<PlayingHandle, AkResult>
     where
         F: FnMut(crate::AkCallbackInfo) + 'static,
     {
         // see http://blog.sagetheprogrammer.com/neat-rust-tricks-passing-rust-closures-to-c
         let data = Box::into_raw(Box::new(callback));
 
-        if let AkID::Name(name) = self.event_id {
-            let ak_playing_id = unsafe {
-                with_cstring![name => cname {
-                    PostEvent2(
-                        cname.as_ptr(),
-                        self.game_obj_id,
-                        (self.flags | AkCallbackType::AK_EndOfEvent).0 as u32,
-                        Some(Self::call_callback_as_closure::<F>),
-                        data as *mut _,
-                        0,                      // TODO
-                        ::std::ptr::null_mut(), // TODO
-                        self.playing_id,
-                    )
-                }]
-            };
-            if ak_playing_id == AK_INVALID_PLAYING_ID {
-                Err(AkResult::AK_Fail)
-            } else {
-                Ok(ak_playing_id)
-            }
-        } else if let AkID::ID(id) = self.event_id {
-            let ak_playing_id = unsafe {
-                PostEvent(
-                    id,
+        let (file_names, external_sources) = self.raw_external_sources();
+        let ak_playing_id = unsafe {
+            self.post_raw(
+                self.flags | AkCallbackType::AK_EndOfEvent,
+                Some(Self::call_callback_as_closure::<F>),
+                data as *mut _,
+                &external_sources,
+            )
+        };
+        drop(file_names);
+
+        if ak_playing_id == AK_INVALID_PLAYING_ID {
+            // Wwise will never call back: cleanup memory
+            drop(unsafe { Box::from_raw(data) });
+            Err(AkResult::AK_Fail)
+        } else {
+            Ok(self.handle(ak_playing_id))
+        }
+    }
+
+    unsafe fn post_raw(
+        &self,
+        flags: AkCallbackType,
+        callback: AkCallbackFunc,
+        cookie: *mut ::std::os::raw::c_void,
+        external_sources: &[bindings::root::AkExternalSourceInfo],
+    ) -> AkPlayingID {
+        // Wwise copies the external sources array, no need to keep it alive after posting
+        let (num_external_sources, external_sources) = if external_sources.is_empty() {
+            (0, ::std::ptr::null_mut())
+        } else {
+            (
+                external_sources.len() as AkUInt32,
+                external_sources.as_ptr() as *mut _,
+            )
+        };
+
+        match self.event_id {
+            AkID::Name(name) => with_cstring![name => cname {
+                PostEvent2(
+                    cname.as_ptr(),
                     self.game_obj_id,
-                    (self.flags | AkCallbackType::AK_EndOfEvent).0 as u32,
-                    Some(Self::call_callback_as_closure::<F>),
-                    data as *mut _,
-                    0,                      // TODO
-                    ::std::ptr::null_mut(), // TODO
+                    flags.0 as u32,
+                    callback,
+                    cookie,
+                    num_external_sources,
+                    external_sources,
                     self.playing_id,
                 )
-            };
-            if ak_playing_id == AK_INVALID_PLAYING_ID {
-                Err(AkResult::AK_Fail)
-            } else {
-                Ok(ak_playing_id)
-            }
-        } else {
-            panic!("need at least an event ID or and an event name to post")
+            }],
+            AkID::ID(id) => PostEvent(
+                id,
+                self.game_obj_id,
+                flags.0 as u32,
+                callback,
+                cookie,
+                num_external_sources,
+                external_sources,
+                self.playing_id,
+            ),
+        }
+    }
+
+    fn memory_buffers(&self) -> impl Iterator<Item = &Arc<[u8]>> {
+        self.external_sources
+            .iter()
+            .filter_map(|source| match &source.data {
+                ExternalSourceData::Memory(bytes) => Some(bytes),
+                _ => None,
+            })
+    }
+
+    /// The file names must outlive the returned external sources.
+    fn raw_external_sources(
+        &self,
+    ) -> (Vec<Vec<OsChar>>, Vec<bindings::root::AkExternalSourceInfo>) {
+        let file_names: Vec<Option<Vec<OsChar>>> = self
+            .external_sources
+            .iter()
+            .map(|source| match &source.data {
+                ExternalSourceData::File(path) => Some(to_os_char(path)),
+                _ => None,
+            })
+            .collect();
+        let external_sources = self
+            .external_sources
+            .iter()
+            .zip(file_names.iter())
+            .map(|(source, file_name)| source.as_ak(file_name.as_ref()))
+            .collect();
+
+        (file_names.into_iter().flatten().collect(), external_sources)
+    }
+
+    fn handle(&self, playing_id: AkPlayingID) -> PlayingHandle {
+        PlayingHandle {
+            playing_id,
+            event_id: match self.event_id {
+                AkID::Name(name) => get_id_from_string(name),
+                AkID::ID(id) => id,
+            },
+            game_obj_id: self.game_obj_id,
+            stop_on_drop: None,
         }
     }
 
@@ -698,3 +1860,285 @@ impl<'a> PostEvent<'a> {
         }
     }
 }
+
+/// Keep the in-memory external sources of the event `playing_id` alive until it ends.
+///
+/// Buffers of events that ended are released on the next call, or when the sound engine is
+/// terminated: this keeps [PostEvent::post] from needing an end of event callback.
+fn keep_external_source_buffers(playing_id: AkPlayingID, buffers: Vec<Arc<[u8]>>) {
+    let mut kept = EXTERNAL_SOURCE_BUFFERS.lock().unwrap();
+    kept.retain(|(playing_id, _)| unsafe {
+        Query::GetEventIDFromPlayingID(*playing_id) != AK_INVALID_UNIQUE_ID
+    });
+    kept.push((playing_id, buffers));
+}
+
+/// Handle over an event instance started with [PostEvent::post] or [PostEvent::post_with_callback].
+///
+/// It can be used to stop, pause or resume that instance only, without posting a dedicated event.
+///
+/// By default, dropping the handle leaves the event playing; use [stop_on_drop](Self::stop_on_drop)
+/// to stop it instead.
+#[derive(Debug)]
+pub struct PlayingHandle {
+    playing_id: AkPlayingID,
+    event_id: AkUniqueID,
+    game_obj_id: AkGameObjectID,
+    stop_on_drop: Option<(AkTimeMs, AkCurveInterpolation)>,
+}
+
+impl PlayingHandle {
+    /// The playing ID of the event instance.
+    pub fn playing_id(&self) -> AkPlayingID {
+        self.playing_id
+    }
+
+    /// The ID of the event that was posted.
+    pub fn event_id(&self) -> AkUniqueID {
+        self.event_id
+    }
+
+    /// The game object on which the event was posted.
+    pub fn game_obj_id(&self) -> AkGameObjectID {
+        self.game_obj_id
+    }
+
+    /// Stop the event instance when this handle is dropped, fading out over `fade_ms` milliseconds
+    /// following `curve`.
+    pub fn stop_on_drop(mut self, fade_ms: AkTimeMs, curve: AkCurveInterpolation) -> Self {
+        self.stop_on_drop = Some((fade_ms, curve));
+        self
+    }
+
+    /// Give up control over the event instance: it keeps playing even if [stop_on_drop](Self::stop_on_drop)
+    /// was set.
+    ///
+    /// *Return* The playing ID of the event instance.
+    pub fn detach(mut self) -> AkPlayingID {
+        self.stop_on_drop = None;
+        self.playing_id
+    }
+
+    /// Stop the event instance, fading out over `fade_ms` milliseconds following `curve`.
+    pub fn stop(&self, fade_ms: AkTimeMs, curve: AkCurveInterpolation) {
+        unsafe {
+            StopPlayingID(self.playing_id, fade_ms, curve);
+        }
+    }
+
+    /// Pause the event instance, fading out over `fade_ms` milliseconds following `curve`.
+    ///
+    /// *See also* [resume](Self::resume)
+    pub fn pause(&self, fade_ms: AkTimeMs, curve: AkCurveInterpolation) -> Result<(), AkResult> {
+        self.execute_action(
+            AkActionOnEventType::AkActionOnEventType_Pause,
+            fade_ms,
+            curve,
+        )
+    }
+
+    /// Resume the event instance after a [pause](Self::pause), fading in over `fade_ms`
+    /// milliseconds following `curve`.
+    pub fn resume(&self, fade_ms: AkTimeMs, curve: AkCurveInterpolation) -> Result<(), AkResult> {
+        self.execute_action(
+            AkActionOnEventType::AkActionOnEventType_Resume,
+            fade_ms,
+            curve,
+        )
+    }
+
+    /// Set an RTPC on this event instance only.
+    ///
+    /// Use [SetRtpcValue](crate::game_syncs::SetRtpcValue) with
+    /// [for_playing_id](crate::game_syncs::SetRtpcValue::for_playing_id) for interpolation options.
+    pub fn set_rtpc<'a, T: Into<AkID<'a>>>(
+        &self,
+        rtpc_id: T,
+        value: AkRtpcValue,
+    ) -> Result<(), AkResult> {
+        crate::game_syncs::SetRtpcValue::new(rtpc_id, value)
+            .for_playing_id(self.playing_id)
+            .set()
+    }
+
+    /// Whether the event instance is still playing on its game object.
+    ///
+    /// *Return* `Ok(false)` once the event has ended, including when its game object was
+    /// unregistered since, or the error of
+    /// [get_playing_ids_from_game_object](crate::query_params::get_playing_ids_from_game_object)
+    /// if the sound engine couldn't be queried.
+    pub fn is_playing(&self) -> Result<bool, AkResult> {
+        match crate::query_params::get_playing_ids_from_game_object(self.game_obj_id) {
+            Ok(playing_ids) => Ok(playing_ids.contains(&self.playing_id)),
+            Err(AkResult::AK_IDNotFound) => Ok(false),
+            Err(akr) => Err(akr),
+        }
+    }
+
+    /// Prepare seeking this event instance only.
+    ///
+    /// *See also* [SeekOnEvent]
+    pub fn seek(&self) -> SeekOnEvent<'static> {
+        SeekOnEvent::new(self.game_obj_id, self.event_id).for_playing_id(self.playing_id)
+    }
+
+    fn execute_action(
+        &self,
+        action: AkActionOnEventType,
+        fade_ms: AkTimeMs,
+        curve: AkCurveInterpolation,
+    ) -> Result<(), AkResult> {
+        ak_call_result![ExecuteActionOnEvent(
+            self.event_id,
+            action,
+            self.game_obj_id,
+            fade_ms,
+            curve,
+            self.playing_id
+        )]
+    }
+}
+
+impl Display for PlayingHandle {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.playing_id)
+    }
+}
+
+impl Drop for PlayingHandle {
+    fn drop(&mut self) {
+        if let Some((fade_ms, curve)) = self.stop_on_drop {
+            if is_initialized() {
+                self.stop(fade_ms, curve);
+            }
+        }
+    }
+}
+
+/// Where to seek with [SeekOnEvent].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SeekPosition {
+    /// Position in milliseconds from the beginning of the sound.
+    Millis(AkTimeMs),
+    /// Position as a fraction of the duration of the sound, in `[0, 1]`.
+    Percent(AkReal32),
+}
+
+/// Helper to seek inside all the playing objects referenced by the Play Actions of an event.
+///
+/// Use [SeekOnEvent::seek] to seek.
+///
+/// *Remarks*
+/// > - This works with all objects of the actor-mixer hierarchy, and also with music segments and
+/// music switch containers.
+/// > - There is a restriction with sounds that play within a continuous sequence. Seeking is ignored
+/// if one of their ancestors is a continuous (random or sequence) container with crossfade or
+/// trigger rate transitions. Seeking is also ignored with sample-accurate transitions, unless
+/// the sound that is currently playing is the first sound of the sequence.
+/// > - Seeking is also ignored with voices that can go virtual with "From Beginning" behavior.
+/// > - Sounds/segments are stopped if the seek position is beyond their end.
+/// > - With music segments, seeking is relative to the entry cue.
+/// > - This function does not apply to the Play Actions of the event that have not been
+/// executed yet because of a delay.
+///
+/// *See also* [PlayingHandle::seek]
+#[derive(Debug, Copy, Clone)]
+pub struct SeekOnEvent<'a> {
+    game_obj_id: AkGameObjectID,
+    event_id: AkID<'a>,
+    position: SeekPosition,
+    seek_to_nearest_marker: bool,
+    playing_id: AkPlayingID,
+}
+
+impl<'a> SeekOnEvent<'a> {
+    /// Select an event by name or by ID, playing on a given game object.
+    pub fn new<T: Into<AkID<'a>>>(game_obj_id: AkGameObjectID, event_id: T) -> Self {
+        Self {
+            game_obj_id,
+            event_id: event_id.into(),
+            position: SeekPosition::Millis(0),
+            seek_to_nearest_marker: false,
+            playing_id: AK_INVALID_PLAYING_ID,
+        }
+    }
+
+    /// Position to seek to.
+    ///
+    /// Default: `SeekPosition::Millis(0)`
+    ///
+    /// *Note* Doesn't consume self so that it's easier to reuse the same SeekOnEvent struct with
+    /// different positions over time.
+    pub fn at(&mut self, position: SeekPosition) -> &mut Self {
+        self.position = position;
+        self
+    }
+
+    /// Shorthand for `at(SeekPosition::Millis(ms))`.
+    pub fn at_millis(&mut self, ms: AkTimeMs) -> &mut Self {
+        self.at(SeekPosition::Millis(ms))
+    }
+
+    /// Shorthand for `at(SeekPosition::Percent(percent))`.
+    pub fn at_percent(&mut self, percent: AkReal32) -> &mut Self {
+        self.at(SeekPosition::Percent(percent))
+    }
+
+    /// If true, the final seeking position will be made equal to the nearest marker.
+    ///
+    /// Default: `false`
+    pub fn to_nearest_marker(mut self, snap: bool) -> Self {
+        self.seek_to_nearest_marker = snap;
+        self
+    }
+
+    /// Only seek the event instance with this playing ID.
+    ///
+    /// Default: [AK_INVALID_PLAYING_ID] (all the instances of the event on the game object)
+    pub fn for_playing_id(mut self, playing_id: AkPlayingID) -> Self {
+        self.playing_id = playing_id;
+        self
+    }
+
+    /// Seek to the selected position.
+    ///
+    /// *Return*
+    /// > - [AK_Success](AkResult::AK_Success) if the event was found
+    /// > - [AK_IDNotFound](AkResult::AK_IDNotFound) if the event name was not resolved to an existing ID
+    pub fn seek(&self) -> Result<(), AkResult> {
+        match (self.event_id, self.position) {
+            (AkID::Name(name), SeekPosition::Millis(ms)) => with_cstring![name => cname {
+                ak_call_result![SeekOnEvent2(
+                    cname.as_ptr(),
+                    self.game_obj_id,
+                    ms,
+                    self.seek_to_nearest_marker,
+                    self.playing_id
+                )]
+            }],
+            (AkID::ID(id), SeekPosition::Millis(ms)) => ak_call_result![SeekOnEvent(
+                id,
+                self.game_obj_id,
+                ms,
+                self.seek_to_nearest_marker,
+                self.playing_id
+            )],
+            (AkID::Name(name), SeekPosition::Percent(percent)) => with_cstring![name => cname {
+                ak_call_result![SeekOnEvent5(
+                    cname.as_ptr(),
+                    self.game_obj_id,
+                    percent,
+                    self.seek_to_nearest_marker,
+                    self.playing_id
+                )]
+            }],
+            (AkID::ID(id), SeekPosition::Percent(percent)) => ak_call_result![SeekOnEvent3(
+                id,
+                self.game_obj_id,
+                percent,
+                self.seek_to_nearest_marker,
+                self.playing_id
+            )],
+        }
+    }
+}
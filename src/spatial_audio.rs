@@ -0,0 +1,1526 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+//! Wwise Spatial Audio: rooms, portals, acoustic geometry and the game objects they affect.
+//!
+//! *Remarks*
+//! > - Spatial Audio must be initialized with [init] after the sound engine has been initialized
+//! with [sound_engine::init](crate::sound_engine::init).
+//! > - There is no separate termination function: Spatial Audio is terminated along with the sound
+//! engine by [sound_engine::term](crate::sound_engine::term).
+//! > - Game objects used as emitters or listeners must still be registered to the sound engine with
+//! [sound_engine::register_game_obj](crate::sound_engine::register_game_obj) first.
+
+use crate::bindings::root::AK::SpatialAudio::*;
+use crate::bindings::root::{
+    AkAcousticSurface, AkDiffractionPathInfo, AkExtent, AkGeometryInstanceParams, AkGeometryParams,
+    AkImageSourceSettings, AkPortalParams, AkReflectionPathInfo, AkRoomParams, AkTriangle,
+    AkVertex,
+};
+use crate::settings::AkSpatialAudioInitSettings;
+use crate::{
+    ak_call_result, with_cstring, AkAcousticTextureID, AkAuxBusID, AkGameObjectID,
+    AkGeometryInstanceID, AkGeometrySetID, AkImageSourceID, AkPortalID, AkReal32, AkResult,
+    AkRoomID, AkTransform, AkUInt32, AkVector, AK_INVALID_AUX_ID, AK_INVALID_GAME_OBJECT,
+};
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Initialize the Spatial Audio module.
+///
+/// *Warning* This function must be called after the base sound engine has been properly initialized.
+///
+/// *Remark* The initial settings should be initialized using [AkSpatialAudioInitSettings::default]
+/// to fill the structure with its default settings. This is not mandatory, but it helps avoid
+/// backward compatibility problems.
+///
+/// *Return*
+/// > - [AK_Success](AkResult::AK_Success) if the initialization was successful
+/// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter) if some settings are invalid
+/// > - [AK_Fail](AkResult::AK_Fail) if Spatial Audio could not be initialized (for instance, if the sound engine is not initialized)
+///
+/// *See also*
+/// > - [AkSpatialAudioInitSettings::default]
+pub fn init(settings: &AkSpatialAudioInitSettings) -> Result<(), AkResult> {
+    ak_call_result![Init(settings)]
+}
+
+/// Assign a game object as the Spatial Audio listener.
+///
+/// There can be only one Spatial Audio listener registered at any given time; `listener_id` will
+/// replace any previously set Spatial Audio listener. The game object passed in must be registered
+/// by the client, at some point, for sound to be heard. It is not necessary to be registered at
+/// the time of calling this function.
+///
+/// If no listener is explicitly registered to Spatial Audio, then a default listener (set via
+/// [sound_engine::set_default_listeners](crate::sound_engine::set_default_listeners)) is selected.
+/// If there are no default listeners, or there are more than one default listeners, then it is
+/// necessary to call `register_listener` to specify which listener to use with Spatial Audio.
+///
+/// *See also*
+/// > - [unregister_listener]
+pub fn register_listener(listener_id: AkGameObjectID) -> Result<(), AkResult> {
+    ak_call_result![RegisterListener(listener_id)]
+}
+
+/// Unregister a game object as a listener in the Spatial Audio API; clean up Spatial Audio
+/// listener data associated with `listener_id`.
+///
+/// If `listener_id` is the current registered listener calling this function will clear the
+/// Spatial Audio listener and Spatial Audio features will be disabled until another listener is
+/// registered. This function is optional - listener are automatically unregistered when their
+/// game object is deleted in the sound engine.
+///
+/// *See also*
+/// > - [register_listener]
+pub fn unregister_listener(listener_id: AkGameObjectID) -> Result<(), AkResult> {
+    ak_call_result![UnregisterListener(listener_id)]
+}
+
+/// Define an inner and outer radius around each sound position for a specified game object.
+///
+/// If the radii are set to 0, the game object is a point source. Inside the inner radius, the
+/// spread of the sound is at its maximum. Between the inner and outer radius, the spread is
+/// interpolated. Outside the outer radius, the spread is computed normally.
+///
+/// *See also* [sound_engine::set_position](crate::sound_engine::set_position)
+pub fn set_game_object_radius(
+    game_obj_id: AkGameObjectID,
+    outer_radius: AkReal32,
+    inner_radius: AkReal32,
+) -> Result<(), AkResult> {
+    ak_call_result![SetGameObjectRadius(game_obj_id, outer_radius, inner_radius)]
+}
+
+/// Set the early reflections order for reflection calculation.
+///
+/// The reflections order indicates the number of times sound can bounce off of a surface. A
+/// higher number requires more CPU resources but results in denser early reflections. Set to 0
+/// to disable reflection processing. Valid range: 0-4.
+///
+/// If `update_paths` is `true`, all paths are re-computed immediately; otherwise, the new order
+/// only applies to paths calculated from then on.
+pub fn set_reflections_order(order: AkUInt32, update_paths: bool) -> Result<(), AkResult> {
+    ak_call_result![SetReflectionsOrder(order, update_paths)]
+}
+
+/// Set the number of rays cast from the listener by the stochastic ray casting engine.
+///
+/// A higher number requires more CPU resources but provides more accurate results. Default is 35.
+pub fn set_number_of_primary_rays(num_rays: AkUInt32) -> Result<(), AkResult> {
+    ak_call_result![SetNumberOfPrimaryRays(num_rays)]
+}
+
+/// Set the Auxiliary Bus to route early reflections into for a given emitter.
+///
+/// This overrides the early reflections bus set in the Wwise authoring tool. Pass
+/// [AK_INVALID_AUX_ID](crate::AK_INVALID_AUX_ID) to revert to the authored bus.
+pub fn set_early_reflections_aux_send(
+    emitter_id: AkGameObjectID,
+    aux_bus_id: AkAuxBusID,
+) -> Result<(), AkResult> {
+    ak_call_result![SetEarlyReflectionsAuxSend(emitter_id, aux_bus_id)]
+}
+
+/// Set the early reflections volume of a given emitter, as a linear gain in `[0, 1]`.
+///
+/// This is multiplied with the early reflections send volume authored in Wwise.
+pub fn set_early_reflections_volume(
+    emitter_id: AkGameObjectID,
+    send_volume: AkReal32,
+) -> Result<(), AkResult> {
+    ak_call_result![SetEarlyReflectionsVolume(emitter_id, send_volume)]
+}
+
+impl AkRoomID {
+    /// The outdoors room, in which every game object is placed until assigned to another room
+    /// with [set_game_object_in_room].
+    pub fn outdoors() -> Self {
+        Self {
+            id: AK_INVALID_GAME_OBJECT,
+        }
+    }
+
+    /// Whether this ID is the one of the outdoors room.
+    pub fn is_outdoors(&self) -> bool {
+        self.id == AK_INVALID_GAME_OBJECT
+    }
+}
+
+impl From<u64> for AkRoomID {
+    fn from(id: u64) -> Self {
+        Self { id }
+    }
+}
+
+impl PartialEq for AkRoomID {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for AkRoomID {}
+
+impl std::hash::Hash for AkRoomID {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+/// Helper to add or update a room.
+///
+/// Use [Room::set] to send the room to Spatial Audio, and [remove_room] to remove it.
+///
+/// A room's reverb is sent to the aux bus set with [with_reverb_aux_bus](Self::with_reverb_aux_bus);
+/// you can refer to the auxiliary busses generated by `rrise-headers` in its `xbus` module.
+///
+/// *Remarks*
+/// > - Rooms are defined by their orientation only: they have no shape. Game objects are placed in
+/// them with [set_game_object_in_room], and rooms are connected together with [Portal]s.
+/// > - The ID of the outdoors room ([AkRoomID::outdoors]) cannot be used for a new room.
+///
+/// *See also*
+/// > - [remove_room]
+/// > - [set_game_object_in_room]
+/// > - [Portal]
+#[derive(Debug, Copy, Clone)]
+pub struct Room<'a> {
+    id: AkRoomID,
+    name: Option<&'a str>,
+    front: AkVector,
+    up: AkVector,
+    reverb_aux_bus: AkAuxBusID,
+    reverb_level: AkReal32,
+    transmission_loss: AkReal32,
+    aux_send_level_to_self: AkReal32,
+    keep_registered: bool,
+    geometry: AkGeometrySetID,
+}
+
+impl<'a> Room<'a> {
+    /// Describe the room identified by `id`.
+    pub fn new<T: Into<AkRoomID>>(id: T) -> Self {
+        Self {
+            id: id.into(),
+            name: None,
+            front: AkVector::from([0., 0., 1.]),
+            up: AkVector::from([0., 1., 0.]),
+            reverb_aux_bus: AK_INVALID_AUX_ID,
+            reverb_level: 1.,
+            transmission_loss: 1.,
+            aux_send_level_to_self: 0.,
+            keep_registered: false,
+            geometry: AK_INVALID_GAME_OBJECT,
+        }
+    }
+
+    /// Name used to identify the room in the Wwise profiler.
+    ///
+    /// Default: no name
+    pub fn with_name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Orientation of the room; `front` and `up` must be normalized and orthogonal.
+    ///
+    /// The orientation is used to spatialize the room reverb, when the reverb bus has 3D
+    /// positioning.
+    ///
+    /// Default: front is `[0, 0, 1]`, up is `[0, 1, 0]`
+    pub fn with_orientation<T: Into<AkVector>>(mut self, front: T, up: T) -> Self {
+        self.front = front.into();
+        self.up = up.into();
+        self
+    }
+
+    /// The reverb aux bus that is associated with this room.
+    ///
+    /// When Spatial Audio is told that a game object is in a particular room, the reverb aux bus
+    /// will be set on that game object. Leave it to [AK_INVALID_AUX_ID] for no reverb.
+    ///
+    /// Default: [AK_INVALID_AUX_ID]
+    pub fn with_reverb_aux_bus(mut self, aux_bus: AkAuxBusID) -> Self {
+        self.reverb_aux_bus = aux_bus;
+        self
+    }
+
+    /// The reverb control value for the send to the reverb aux bus, in `[0, 1]`.
+    ///
+    /// Default: `1`
+    pub fn with_reverb_level(mut self, level: AkReal32) -> Self {
+        self.reverb_level = level;
+        self
+    }
+
+    /// Transmission loss applied to sounds that go through the walls of this room, in `[0, 1]`.
+    ///
+    /// Default: `1`
+    pub fn with_transmission_loss(mut self, loss: AkReal32) -> Self {
+        self.transmission_loss = loss;
+        self
+    }
+
+    /// Send level for sounds that are posted on the room game object itself, towards the room
+    /// reverb.
+    ///
+    /// Default: `0`
+    pub fn with_aux_send_level_to_self(mut self, level: AkReal32) -> Self {
+        self.aux_send_level_to_self = level;
+        self
+    }
+
+    /// If `true`, the room game object stays registered with the sound engine even when no
+    /// listener is in the room, which allows posting events on it at any time.
+    ///
+    /// Default: `false`
+    pub fn keep_registered(mut self, should: bool) -> Self {
+        self.keep_registered = should;
+        self
+    }
+
+    /// Geometry set describing the walls of this room, added with [Geometry::set].
+    ///
+    /// When set, Spatial Audio uses it to compute the room's transmission paths more accurately.
+    ///
+    /// Default: no geometry
+    pub fn with_geometry(mut self, geometry: AkGeometrySetID) -> Self {
+        self.geometry = geometry;
+        self
+    }
+
+    /// Add or update this room in Spatial Audio.
+    ///
+    /// *Return*
+    /// > - [AK_Success](AkResult::AK_Success) if successful
+    /// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter) if the room ID is the one of the outdoors room, or if the parameters are not valid
+    pub fn set(&self) -> Result<(), AkResult> {
+        let mut params: AkRoomParams = unsafe { std::mem::zeroed() };
+        params.Front = self.front;
+        params.Up = self.up;
+        params.ReverbAuxBus = self.reverb_aux_bus;
+        params.ReverbLevel = self.reverb_level;
+        params.TransmissionLoss = self.transmission_loss;
+        params.RoomGameObj_AuxSendLevelToSelf = self.aux_send_level_to_self;
+        params.RoomGameObj_KeepRegistered = self.keep_registered;
+        params.GeometryID = self.geometry;
+
+        match self.name {
+            Some(name) => with_cstring![name => cname {
+                ak_call_result![SetRoom(self.id, &params, cname.as_ptr())]
+            }],
+            None => ak_call_result![SetRoom(self.id, &params, std::ptr::null())],
+        }
+    }
+}
+
+/// Remove a room.
+///
+/// Game objects that were in this room are put back in the outdoors room.
+///
+/// *See also* [Room]
+pub fn remove_room<T: Into<AkRoomID>>(id: T) -> Result<(), AkResult> {
+    ak_call_result![RemoveRoom(id.into())]
+}
+
+/// Helper to add or update an acoustic portal.
+///
+/// Use [Portal::set] to send the portal to Spatial Audio, and [remove_portal] to remove it.
+///
+/// A portal is an opening (a door, a window...) between two rooms, through which sound can
+/// propagate. Portals are what gives Spatial Audio its diffraction paths between rooms.
+///
+/// *Remarks*
+/// > - The portal is a box defined by [AkTransform] (its center and orientation) and its
+/// half-extents. Its front face points towards the room set as `front_room` in [connecting](Self::connecting).
+/// > - Both rooms should be added with [Room::set] before the portal; the outdoors room
+/// ([AkRoomID::outdoors]) always exists.
+///
+/// *See also*
+/// > - [remove_portal]
+/// > - [set_portal_obstruction_and_occlusion]
+/// > - [Room]
+#[derive(Debug, Copy, Clone)]
+pub struct Portal<'a> {
+    id: AkPortalID,
+    name: Option<&'a str>,
+    transform: AkTransform,
+    half_extents: AkVector,
+    enabled: bool,
+    front_room: AkRoomID,
+    back_room: AkRoomID,
+}
+
+impl<'a> Portal<'a> {
+    /// Describe the portal identified by `id`, centered and oriented as given by `transform`, and
+    /// with the size given by `half_extents` (half width on X, half height on Y and half depth on Z).
+    pub fn new<T: Into<AkTransform>, U: Into<AkVector>>(
+        id: AkPortalID,
+        transform: T,
+        half_extents: U,
+    ) -> Self {
+        Self {
+            id,
+            name: None,
+            transform: transform.into(),
+            half_extents: half_extents.into(),
+            enabled: true,
+            front_room: AkRoomID::outdoors(),
+            back_room: AkRoomID::outdoors(),
+        }
+    }
+
+    /// Name used to identify the portal in the Wwise profiler.
+    ///
+    /// Default: no name
+    pub fn with_name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// The rooms this portal connects. `front_room` is the room the portal's front face points to.
+    ///
+    /// Default: both sides are [outdoors](AkRoomID::outdoors)
+    pub fn connecting<T: Into<AkRoomID>>(mut self, front_room: T, back_room: T) -> Self {
+        self.front_room = front_room.into();
+        self.back_room = back_room.into();
+        self
+    }
+
+    /// Whether the portal is open (`true`) or closed (`false`). A closed portal lets no sound go
+    /// through, except by transmission.
+    ///
+    /// Default: `true`
+    pub fn enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Add or update this portal in Spatial Audio.
+    ///
+    /// *Return*
+    /// > - [AK_Success](AkResult::AK_Success) if successful
+    /// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter) if the parameters are not valid (for instance, if the orientation vectors are not normalized)
+    pub fn set(&self) -> Result<(), AkResult> {
+        let mut params: AkPortalParams = unsafe { std::mem::zeroed() };
+        params.Transform = self.transform;
+        params.Extent = AkExtent {
+            halfWidth: self.half_extents.X,
+            halfHeight: self.half_extents.Y,
+            halfDepth: self.half_extents.Z,
+        };
+        params.bEnabled = self.enabled;
+        params.FrontRoom = self.front_room;
+        params.BackRoom = self.back_room;
+
+        match self.name {
+            Some(name) => with_cstring![name => cname {
+                ak_call_result![SetPortal(self.id, &params, cname.as_ptr())]
+            }],
+            None => ak_call_result![SetPortal(self.id, &params, std::ptr::null())],
+        }
+    }
+}
+
+/// Remove a portal.
+///
+/// *See also* [Portal]
+pub fn remove_portal(id: AkPortalID) -> Result<(), AkResult> {
+    ak_call_result![RemovePortal(id)]
+}
+
+/// Set the room that the game object is currently located in.
+///
+/// Usually, the room is set on the listener and on the emitters; a game object that is not in a
+/// room is outdoors ([AkRoomID::outdoors]).
+///
+/// *Return*
+/// > - [AK_Success](AkResult::AK_Success) if successful
+/// > - [AK_IDNotFound](AkResult::AK_IDNotFound) if the game object was not registered
+pub fn set_game_object_in_room<T: Into<AkRoomID>>(
+    game_obj_id: AkGameObjectID,
+    room_id: T,
+) -> Result<(), AkResult> {
+    ak_call_result![SetGameObjectInRoom(game_obj_id, room_id.into())]
+}
+
+/// Set the obstruction and occlusion values of a portal, both in `[0, 1]`.
+///
+/// Obstruction affects the direct path through the portal; occlusion affects all paths, including
+/// the room's reverb. Useful to simulate a door that gets closed, for instance.
+pub fn set_portal_obstruction_and_occlusion(
+    portal_id: AkPortalID,
+    obstruction: AkReal32,
+    occlusion: AkReal32,
+) -> Result<(), AkResult> {
+    ak_call_result![SetPortalObstructionAndOcclusion(
+        portal_id,
+        obstruction,
+        occlusion
+    )]
+}
+
+/// Error returned when a [Geometry] can't be sent to Spatial Audio.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GeometryError {
+    /// There are more vertices than what Spatial Audio can index (65535).
+    TooManyVertices(usize),
+    /// There are more triangles than what Spatial Audio can index (65535).
+    TooManyTriangles(usize),
+    /// There are more surfaces than what Spatial Audio can index (65534).
+    TooManySurfaces(usize),
+    /// The vertex at this index has a NaN or infinite component.
+    InvalidVertex { vertex: usize },
+    /// The triangle at index `triangle` refers to a vertex that doesn't exist.
+    VertexOutOfRange { triangle: usize, vertex: u16 },
+    /// The triangle at index `triangle` refers to a surface that doesn't exist.
+    SurfaceOutOfRange { triangle: usize, surface: u16 },
+    /// The name of the surface at this index contains a NUL byte.
+    InvalidSurfaceName { surface: usize },
+    /// The triangle at index `triangle` has no area: two of its points are the same, or its points
+    /// are aligned (its area is negligible compared to the length of its edges).
+    DegenerateTriangle { triangle: usize },
+    /// The geometry was valid, but Wwise still refused it.
+    Wwise(AkResult),
+}
+
+impl std::error::Error for GeometryError {}
+
+impl Display for GeometryError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooManyVertices(n) => write!(f, "too many vertices: {} (max 65535)", n),
+            Self::TooManyTriangles(n) => write!(f, "too many triangles: {} (max 65535)", n),
+            Self::TooManySurfaces(n) => write!(f, "too many surfaces: {} (max 65534)", n),
+            Self::InvalidVertex { vertex } => {
+                write!(f, "vertex {} has a NaN or infinite component", vertex)
+            }
+            Self::VertexOutOfRange { triangle, vertex } => {
+                write!(
+                    f,
+                    "triangle {} refers to unknown vertex {}",
+                    triangle, vertex
+                )
+            }
+            Self::SurfaceOutOfRange { triangle, surface } => {
+                write!(
+                    f,
+                    "triangle {} refers to unknown surface {}",
+                    triangle, surface
+                )
+            }
+            Self::InvalidSurfaceName { surface } => {
+                write!(f, "the name of surface {} contains a NUL byte", surface)
+            }
+            Self::DegenerateTriangle { triangle } => {
+                write!(f, "triangle {} is degenerate (it has no area)", triangle)
+            }
+            Self::Wwise(akr) => write!(f, "{}", akr),
+        }
+    }
+}
+
+impl From<AkResult> for GeometryError {
+    fn from(akr: AkResult) -> Self {
+        Self::Wwise(akr)
+    }
+}
+
+/// Held while geometry sets are added, updated or removed, and while [query_reflection_paths]
+/// copies the surfaces of Spatial Audio's geometry, so that they can't be freed during the copy.
+static GEOMETRY_LOCK: Mutex<()> = Mutex::new(());
+
+/// Ratio of the double area of a triangle to the square of its longest edge under which it is
+/// considered degenerate.
+const DEGENERATE_TRIANGLE_TOLERANCE: AkReal32 = 16. * f32::EPSILON;
+
+/// Acoustic properties of the surfaces of a [Geometry].
+#[derive(Debug, Copy, Clone)]
+pub struct AcousticSurface<'a> {
+    /// Acoustic texture ID of the surface, as defined in the Virtual Acoustics hierarchy of the
+    /// Wwise project; `0` for no texture (the surface reflects everything).
+    pub texture_id: AkAcousticTextureID,
+    /// Transmission loss of sounds going through this surface, in `[0, 1]`.
+    pub transmission_loss: AkReal32,
+    /// Name of the surface, to identify it in the Wwise profiler.
+    pub name: Option<&'a str>,
+}
+
+impl<'a> AcousticSurface<'a> {
+    /// A surface with the acoustic texture `texture_id`, that lets no sound through.
+    pub fn new(texture_id: AkAcousticTextureID) -> Self {
+        Self {
+            texture_id,
+            transmission_loss: 1.,
+            name: None,
+        }
+    }
+}
+
+/// A triangle of a [Geometry], referring to its vertices and surfaces by index.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Triangle {
+    /// Indices of the 3 points of the triangle in [Geometry]'s vertices.
+    pub points: [u16; 3],
+    /// Index of the acoustic surface of this triangle in [Geometry]'s surfaces; [None] to use the
+    /// default surface (no acoustic texture, no transmission).
+    pub surface: Option<u16>,
+}
+
+impl Triangle {
+    /// A triangle made of the vertices at indices `points`, with the surface at index `surface`
+    /// ([None] for the default surface).
+    pub fn new(points: [u16; 3], surface: Option<u16>) -> Self {
+        Self { points, surface }
+    }
+}
+
+/// Helper to add or update a geometry set, typically built from a collision mesh.
+///
+/// Use [Geometry::set] to send the geometry to Spatial Audio, and [remove_geometry] to remove
+/// it. The geometry is validated before being sent: degenerate triangles, indices out of range or
+/// invalid vertices are reported with a [GeometryError] instead of a generic [AkResult::AK_Fail].
+///
+/// Geometry is used by Spatial Audio to compute early reflections (with the Reflect plug-in) and
+/// diffraction around edges. A geometry set is placed in the world by one or more
+/// [GeometryInstance]s.
+///
+/// *Remark* Wwise copies the geometry data: `vertices`, `triangles` and `surfaces` don't need to
+/// outlive the call to [set](Self::set).
+///
+/// *See also*
+/// > - [remove_geometry]
+/// > - [GeometryInstance]
+#[derive(Debug, Copy, Clone)]
+pub struct Geometry<'a> {
+    id: AkGeometrySetID,
+    vertices: &'a [AkVector],
+    triangles: &'a [Triangle],
+    surfaces: &'a [AcousticSurface<'a>],
+    enable_diffraction: bool,
+    enable_diffraction_on_boundary_edges: bool,
+}
+
+impl<'a> Geometry<'a> {
+    /// Describe the geometry set `id` made of `triangles`, whose points are indices in `vertices`.
+    pub fn new(id: AkGeometrySetID, vertices: &'a [AkVector], triangles: &'a [Triangle]) -> Self {
+        Self {
+            id,
+            vertices,
+            triangles,
+            surfaces: &[],
+            enable_diffraction: false,
+            enable_diffraction_on_boundary_edges: false,
+        }
+    }
+
+    /// The acoustic surfaces the triangles refer to.
+    ///
+    /// Default: no surfaces
+    pub fn with_surfaces(mut self, surfaces: &'a [AcousticSurface<'a>]) -> Self {
+        self.surfaces = surfaces;
+        self
+    }
+
+    /// Whether Spatial Audio should compute diffraction paths around the edges of this geometry.
+    ///
+    /// Default: `false`
+    pub fn enable_diffraction(mut self, should: bool) -> Self {
+        self.enable_diffraction = should;
+        self
+    }
+
+    /// Whether Spatial Audio should compute diffraction paths around the boundary edges of this
+    /// geometry (the edges that are part of only one triangle). Ignored if diffraction is not
+    /// enabled with [enable_diffraction](Self::enable_diffraction).
+    ///
+    /// Default: `false`
+    pub fn enable_diffraction_on_boundary_edges(mut self, should: bool) -> Self {
+        self.enable_diffraction_on_boundary_edges = should;
+        self
+    }
+
+    /// Check that this geometry can be sent to Spatial Audio.
+    ///
+    /// This is done by [set](Self::set); call it yourself to validate meshes ahead of time.
+    pub fn validate(&self) -> Result<(), GeometryError> {
+        if self.vertices.len() > u16::MAX as usize {
+            return Err(GeometryError::TooManyVertices(self.vertices.len()));
+        }
+        if self.triangles.len() > u16::MAX as usize {
+            return Err(GeometryError::TooManyTriangles(self.triangles.len()));
+        }
+        // u16::MAX is AK_INVALID_SURFACE
+        if self.surfaces.len() >= u16::MAX as usize {
+            return Err(GeometryError::TooManySurfaces(self.surfaces.len()));
+        }
+
+        if let Some(vertex) = self
+            .vertices
+            .iter()
+            .position(|v| !(v.X.is_finite() && v.Y.is_finite() && v.Z.is_finite()))
+        {
+            return Err(GeometryError::InvalidVertex { vertex });
+        }
+
+        if let Some(surface) = self
+            .surfaces
+            .iter()
+            .position(|s| s.name.map_or(false, |name| name.contains('\0')))
+        {
+            return Err(GeometryError::InvalidSurfaceName { surface });
+        }
+
+        for (i, triangle) in self.triangles.iter().enumerate() {
+            if let Some(vertex) = triangle
+                .points
+                .iter()
+                .find(|p| **p as usize >= self.vertices.len())
+            {
+                return Err(GeometryError::VertexOutOfRange {
+                    triangle: i,
+                    vertex: *vertex,
+                });
+            }
+
+            if let Some(surface) = triangle.surface {
+                if surface as usize >= self.surfaces.len() {
+                    return Err(GeometryError::SurfaceOutOfRange {
+                        triangle: i,
+                        surface,
+                    });
+                }
+            }
+
+            let [a, b, c] = triangle.points.map(|p| self.vertices[p as usize]);
+            let (ab, ac, bc) = (
+                [b.X - a.X, b.Y - a.Y, b.Z - a.Z],
+                [c.X - a.X, c.Y - a.Y, c.Z - a.Z],
+                [c.X - b.X, c.Y - b.Y, c.Z - b.Z],
+            );
+            let cross = [
+                ab[1] * ac[2] - ab[2] * ac[1],
+                ab[2] * ac[0] - ab[0] * ac[2],
+                ab[0] * ac[1] - ab[1] * ac[0],
+            ];
+            // Compare the area to the longest edge rather than to an absolute epsilon, so that
+            // small-scale geometry is not rejected
+            let longest_edge_sq = [ab, ac, bc]
+                .iter()
+                .map(|e| e[0] * e[0] + e[1] * e[1] + e[2] * e[2])
+                .fold(0., f32::max);
+            let double_area_sq = cross[0] * cross[0] + cross[1] * cross[1] + cross[2] * cross[2];
+            let tolerance = DEGENERATE_TRIANGLE_TOLERANCE * longest_edge_sq;
+            if double_area_sq <= tolerance * tolerance {
+                return Err(GeometryError::DegenerateTriangle { triangle: i });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate this geometry then add or update it in Spatial Audio.
+    ///
+    /// *Return*
+    /// > - `Ok(())` if successful
+    /// > - A [GeometryError] describing the problem if the geometry is not valid
+    /// > - [GeometryError::Wwise] if Wwise refused the geometry anyway
+    pub fn set(&self) -> Result<(), GeometryError> {
+        self.validate()?;
+
+        let mut vertices = self
+            .vertices
+            .iter()
+            .map(|v| AkVertex {
+                X: v.X,
+                Y: v.Y,
+                Z: v.Z,
+            })
+            .collect::<Vec<_>>();
+        let mut triangles = self
+            .triangles
+            .iter()
+            .map(|t| AkTriangle {
+                point0: t.points[0],
+                point1: t.points[1],
+                point2: t.points[2],
+                surface: t.surface.unwrap_or(u16::MAX),
+            })
+            .collect::<Vec<_>>();
+        let names = self
+            .surfaces
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                s.name
+                    .map(|name| CString::new(name))
+                    .transpose()
+                    .map_err(|_| GeometryError::InvalidSurfaceName { surface: i })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut surfaces = self
+            .surfaces
+            .iter()
+            .zip(names.iter())
+            .map(|(s, name)| AkAcousticSurface {
+                textureID: s.texture_id,
+                transmissionLoss: s.transmission_loss,
+                strName: name.as_ref().map_or(std::ptr::null(), |n| n.as_ptr()),
+            })
+            .collect::<Vec<_>>();
+
+        let mut params: AkGeometryParams = unsafe { std::mem::zeroed() };
+        params.Triangles = triangles.as_mut_ptr();
+        params.NumTriangles = triangles.len() as u16;
+        params.Vertices = vertices.as_mut_ptr();
+        params.NumVertices = vertices.len() as u16;
+        params.Surfaces = surfaces.as_mut_ptr();
+        params.NumSurfaces = surfaces.len() as u16;
+        params.EnableDiffraction = self.enable_diffraction;
+        params.EnableDiffractionOnBoundaryEdges = self.enable_diffraction_on_boundary_edges;
+
+        let _geometry = GEOMETRY_LOCK.lock().unwrap();
+        Ok(ak_call_result![SetGeometry(self.id, &params)]?)
+    }
+}
+
+/// Remove a geometry set, and all the geometry instances that refer to it.
+///
+/// *See also* [Geometry]
+pub fn remove_geometry(id: AkGeometrySetID) -> Result<(), AkResult> {
+    let _geometry = GEOMETRY_LOCK.lock().unwrap();
+    ak_call_result![RemoveGeometry(id)]
+}
+
+/// Helper to add or update an instance of a [Geometry] set in the world.
+///
+/// Use [GeometryInstance::set] to send the instance to Spatial Audio, and
+/// [remove_geometry_instance] to remove it.
+///
+/// *See also*
+/// > - [remove_geometry_instance]
+/// > - [Geometry]
+#[derive(Debug, Copy, Clone)]
+pub struct GeometryInstance {
+    id: AkGeometryInstanceID,
+    geometry: AkGeometrySetID,
+    transform: AkTransform,
+    scale: AkVector,
+    room: AkRoomID,
+}
+
+impl GeometryInstance {
+    /// Describe the instance `id` of the geometry set `geometry`.
+    pub fn new(id: AkGeometryInstanceID, geometry: AkGeometrySetID) -> Self {
+        Self {
+            id,
+            geometry,
+            transform: AkTransform::default(),
+            scale: AkVector::splat(1.),
+            room: AkRoomID::outdoors(),
+        }
+    }
+
+    /// Position and orientation of the instance in the world.
+    ///
+    /// Default: [AkTransform::default]
+    pub fn with_transform<T: Into<AkTransform>>(mut self, transform: T) -> Self {
+        self.transform = transform.into();
+        self
+    }
+
+    /// Scale of the instance, applied to the geometry set before its transform.
+    ///
+    /// Default: `[1, 1, 1]`
+    pub fn with_scale<T: Into<AkVector>>(mut self, scale: T) -> Self {
+        self.scale = scale.into();
+        self
+    }
+
+    /// Room in which this instance is; it will only reflect and diffract sounds from that room.
+    /// Leave it to [outdoors](AkRoomID::outdoors) for the instance to be considered in all rooms.
+    ///
+    /// Default: [AkRoomID::outdoors]
+    pub fn in_room<T: Into<AkRoomID>>(mut self, room: T) -> Self {
+        self.room = room.into();
+        self
+    }
+
+    /// Add or update this geometry instance in Spatial Audio.
+    ///
+    /// *Return*
+    /// > - [AK_Success](AkResult::AK_Success) if successful
+    /// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter) if the parameters are not valid
+    pub fn set(&self) -> Result<(), AkResult> {
+        let mut params: AkGeometryInstanceParams = unsafe { std::mem::zeroed() };
+        params.PositionAndOrientation = self.transform;
+        params.Scale = self.scale;
+        params.GeometrySetID = self.geometry;
+        params.RoomID = self.room;
+
+        ak_call_result![SetGeometryInstance(self.id, &params)]
+    }
+}
+
+/// Remove a geometry instance.
+///
+/// *See also* [GeometryInstance]
+pub fn remove_geometry_instance(id: AkGeometryInstanceID) -> Result<(), AkResult> {
+    ak_call_result![RemoveGeometryInstance(id)]
+}
+
+/// Helper to add or update an image source, for hand-placed early reflections rendered by the
+/// Reflect plug-in.
+///
+/// An image source is a virtual emitter that the Reflect plug-in instance on `aux_bus` renders as
+/// an early reflection of the sounds it receives. Image sources set this way are independent from
+/// the ones Spatial Audio computes from [Geometry].
+///
+/// *See also*
+/// > - [remove_image_source]
+/// > - [clear_image_sources]
+#[derive(Debug, Copy, Clone)]
+pub struct ImageSource<'a> {
+    id: AkImageSourceID,
+    position: AkVector,
+    aux_bus: AkAuxBusID,
+    room: AkRoomID,
+    game_obj_id: AkGameObjectID,
+    distance_scaling_factor: AkReal32,
+    level: AkReal32,
+    textures: &'a [AkAcousticTextureID],
+}
+
+impl<'a> ImageSource<'a> {
+    /// Describe the image source `id` at `position`, rendered by the Reflect plug-in on the aux bus
+    /// `aux_bus`.
+    pub fn new<T: Into<AkVector>>(id: AkImageSourceID, position: T, aux_bus: AkAuxBusID) -> Self {
+        Self {
+            id,
+            position: position.into(),
+            aux_bus,
+            room: AkRoomID::outdoors(),
+            game_obj_id: AK_INVALID_GAME_OBJECT,
+            distance_scaling_factor: 1.,
+            level: 1.,
+            textures: &[],
+        }
+    }
+
+    /// Room in which the image source is; it only affects emitters in that room.
+    ///
+    /// Default: [AkRoomID::outdoors]
+    pub fn in_room<T: Into<AkRoomID>>(mut self, room: T) -> Self {
+        self.room = room.into();
+        self
+    }
+
+    /// Restrict the image source to the sounds of a single emitter.
+    ///
+    /// Default: [AK_INVALID_GAME_OBJECT] (all emitters sending to `aux_bus`)
+    pub fn for_game_object(mut self, game_obj_id: AkGameObjectID) -> Self {
+        self.game_obj_id = game_obj_id;
+        self
+    }
+
+    /// Factor applied to the distance between the image source and the listener before the
+    /// Reflect plug-in computes its attenuation.
+    ///
+    /// Default: `1`
+    pub fn with_distance_scaling_factor(mut self, factor: AkReal32) -> Self {
+        self.distance_scaling_factor = factor;
+        self
+    }
+
+    /// Level of the reflection, in `[0, 1]`.
+    ///
+    /// Default: `1`
+    pub fn with_level(mut self, level: AkReal32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Acoustic textures filtering the reflection (at most 4).
+    ///
+    /// Default: no texture
+    pub fn with_textures(mut self, textures: &'a [AkAcousticTextureID]) -> Self {
+        self.textures = textures;
+        self
+    }
+
+    /// Add or update this image source.
+    ///
+    /// *Return*
+    /// > - [AK_Success](AkResult::AK_Success) if successful
+    /// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter) if more than 4 textures were given, or if the parameters are not valid
+    pub fn set(&self) -> Result<(), AkResult> {
+        let mut settings: AkImageSourceSettings = unsafe { std::mem::zeroed() };
+        if self.textures.len() > settings.texture.arTextureID.len() {
+            return Err(AkResult::AK_InvalidParameter);
+        }
+
+        settings.params.sourcePosition = self.position;
+        settings.params.fDistanceScalingFactor = self.distance_scaling_factor;
+        settings.params.fLevel = self.level;
+        settings.texture.uNumTexture = self.textures.len() as AkUInt32;
+        settings.texture.arTextureID[..self.textures.len()].copy_from_slice(self.textures);
+
+        ak_call_result![SetImageSource(
+            self.id,
+            &settings,
+            self.aux_bus,
+            self.room,
+            self.game_obj_id
+        )]
+    }
+}
+
+/// Remove an image source added with [ImageSource::set].
+///
+/// `aux_bus` and `game_obj_id` must match the ones the image source was set with.
+pub fn remove_image_source(
+    id: AkImageSourceID,
+    aux_bus: AkAuxBusID,
+    game_obj_id: Option<AkGameObjectID>,
+) -> Result<(), AkResult> {
+    ak_call_result![RemoveImageSource(
+        id,
+        aux_bus,
+        game_obj_id.unwrap_or(AK_INVALID_GAME_OBJECT)
+    )]
+}
+
+/// Remove all the image sources added with [ImageSource::set].
+///
+/// If `aux_bus` is [None], the image sources of every aux bus are removed; if `game_obj_id` is
+/// [None], the image sources of every game object are removed.
+pub fn clear_image_sources(
+    aux_bus: Option<AkAuxBusID>,
+    game_obj_id: Option<AkGameObjectID>,
+) -> Result<(), AkResult> {
+    ak_call_result![ClearImageSources(
+        aux_bus.unwrap_or(AK_INVALID_AUX_ID),
+        game_obj_id.unwrap_or(AK_INVALID_GAME_OBJECT)
+    )]
+}
+
+/// Paths between an emitter and the Spatial Audio listener, as returned by
+/// [query_diffraction_paths] and [query_reflection_paths].
+#[derive(Debug, Clone)]
+pub struct QueriedPaths<T> {
+    /// Position of the listener when the paths were computed.
+    pub listener_position: AkVector,
+    /// Position of the emitter when the paths were computed.
+    pub emitter_position: AkVector,
+    /// The paths themselves.
+    pub paths: Vec<T>,
+}
+
+/// A point of a [DiffractionPath] where sound bends, either around the edge of a geometry or
+/// through a portal.
+#[derive(Debug, Copy, Clone)]
+pub struct DiffractionNode {
+    /// Position of the node.
+    pub position: AkVector,
+    /// Angle (in radians) by which the sound is diffracted at this node; 0 means no diffraction.
+    pub angle: AkReal32,
+    /// The portal this node is in, or [None] if this node is on the edge of a geometry.
+    pub portal: Option<AkPortalID>,
+}
+
+/// A path along which sound travels from an emitter to the listener, bending around edges or
+/// going through portals.
+#[derive(Debug, Clone)]
+pub struct DiffractionPath {
+    /// The nodes of the path, from the listener to the emitter.
+    pub nodes: Vec<DiffractionNode>,
+    /// The rooms the path goes through, from the listener to the emitter: there is always one
+    /// more room than there are nodes.
+    pub rooms: Vec<AkRoomID>,
+    /// Virtual position of the emitter, as perceived by the listener through this path.
+    pub virtual_position: AkTransform,
+    /// Total diffraction of the path, in `[0, 1]` (the sum of all the angles, normalized).
+    pub diffraction: AkReal32,
+    /// Transmission loss applied to this path, in `[0, 1]`.
+    pub transmission_loss: AkReal32,
+    /// Length of the path from the listener to the emitter.
+    pub total_length: AkReal32,
+    /// Obstruction value of the path, in `[0, 1]`.
+    pub obstruction: AkReal32,
+}
+
+impl From<&AkDiffractionPathInfo> for DiffractionPath {
+    fn from(info: &AkDiffractionPathInfo) -> Self {
+        let node_count = info.nodeCount as usize;
+        Self {
+            nodes: info
+                .nodes
+                .iter()
+                .zip(info.angles.iter())
+                .zip(info.portals.iter())
+                .take(node_count)
+                .map(|((position, angle), portal)| DiffractionNode {
+                    position: *position,
+                    angle: *angle,
+                    portal: if *portal == AK_INVALID_GAME_OBJECT {
+                        None
+                    } else {
+                        Some(*portal)
+                    },
+                })
+                .collect(),
+            rooms: info.rooms.iter().take(node_count + 1).copied().collect(),
+            virtual_position: info.virtualPos,
+            diffraction: info.diffraction,
+            transmission_loss: info.transmissionLoss,
+            total_length: info.totalLength,
+            obstruction: info.obstructionValue,
+        }
+    }
+}
+
+/// Owned copy of the [AcousticSurface] a [ReflectionPath] bounces off.
+#[derive(Debug, Clone)]
+pub struct ReflectionSurface {
+    /// Acoustic texture ID of the surface.
+    pub texture_id: AkAcousticTextureID,
+    /// Transmission loss of the surface, in `[0, 1]`.
+    pub transmission_loss: AkReal32,
+    /// Name of the surface, if it was given one.
+    pub name: Option<String>,
+}
+
+/// A point of a [ReflectionPath], where sound bounces off a surface or bends around an edge.
+#[derive(Debug, Clone)]
+pub struct ReflectionPoint {
+    /// Position of the point.
+    pub position: AkVector,
+    /// The surface hit at this point, if any.
+    pub surface: Option<ReflectionSurface>,
+    /// Diffraction at this point, in `[0, 1]`.
+    pub diffraction: AkReal32,
+}
+
+/// An early reflection path from an emitter to the listener, as computed by Spatial Audio for the
+/// Reflect plug-in.
+#[derive(Debug, Clone)]
+pub struct ReflectionPath {
+    /// Position of the image source of this path: the virtual emitter the reflection seems to
+    /// come from.
+    pub image_source: AkVector,
+    /// The points of the path, from the emitter to the listener.
+    pub points: Vec<ReflectionPoint>,
+    /// Number of reflections in the path; other points are diffraction points.
+    pub num_reflections: AkUInt32,
+    /// Level of the reflection, in `[0, 1]`.
+    pub level: AkReal32,
+    /// Whether the path is occluded by geometry.
+    pub occluded: bool,
+}
+
+impl ReflectionPath {
+    /// Copy `info`, including the surfaces it points to.
+    ///
+    /// *Safety* The surfaces of `info` must still be alive: [GEOMETRY_LOCK] must have been held
+    /// since they were queried.
+    unsafe fn copy_from(info: &AkReflectionPathInfo) -> Self {
+        Self {
+            image_source: info.imageSource,
+            points: info
+                .pathPoint
+                .iter()
+                .zip(info.surfaces.iter())
+                .zip(info.diffraction.iter())
+                .take(info.numPathPoints as usize)
+                .map(|((position, surface), diffraction)| ReflectionPoint {
+                    position: *position,
+                    surface: surface.as_ref().map(|surface| ReflectionSurface {
+                        texture_id: surface.textureID,
+                        transmission_loss: surface.transmissionLoss,
+                        name: if surface.strName.is_null() {
+                            None
+                        } else {
+                            Some(
+                                CStr::from_ptr(surface.strName)
+                                    .to_string_lossy()
+                                    .into_owned(),
+                            )
+                        },
+                    }),
+                    diffraction: *diffraction,
+                })
+                .collect(),
+            num_reflections: info.numReflections,
+            level: info.level,
+            occluded: info.isOccluded,
+        }
+    }
+}
+
+/// Query information about the diffraction state of an emitter, for debug visualisation.
+///
+/// Returns at most `max_paths` paths for the position `position_index` of `game_obj_id` (0 unless
+/// the game object has multiple positions). The paths are owned copies; they don't change when
+/// Spatial Audio updates its internal state.
+///
+/// *Return*
+/// > - The paths if successful
+/// > - [AK_IDNotFound](AkResult::AK_IDNotFound) if the game object is not registered
+/// > - [AK_Fail](AkResult::AK_Fail) if Spatial Audio is not initialized
+///
+/// *See also*
+/// > - [query_reflection_paths]
+/// > - [query_wet_diffraction]
+pub fn query_diffraction_paths(
+    game_obj_id: AkGameObjectID,
+    position_index: AkUInt32,
+    max_paths: usize,
+) -> Result<QueriedPaths<DiffractionPath>, AkResult> {
+    let mut listener_position = AkVector::default();
+    let mut emitter_position = AkVector::default();
+    let mut raw_paths = (0..max_paths)
+        .map(|_| unsafe { std::mem::zeroed::<AkDiffractionPathInfo>() })
+        .collect::<Vec<_>>();
+    let mut num_paths = max_paths as AkUInt32;
+
+    ak_call_result![QueryDiffractionPaths(
+        game_obj_id,
+        position_index,
+        &mut listener_position,
+        &mut emitter_position,
+        raw_paths.as_mut_ptr(),
+        &mut num_paths
+    )]?;
+
+    Ok(QueriedPaths {
+        listener_position,
+        emitter_position,
+        paths: raw_paths
+            .iter()
+            .take(num_paths as usize)
+            .map(DiffractionPath::from)
+            .collect(),
+    })
+}
+
+/// Query information about the reflection paths of an emitter, as computed for the Reflect
+/// plug-in, for debug visualisation.
+///
+/// Returns at most `max_paths` paths for the position `position_index` of `game_obj_id` (0 unless
+/// the game object has multiple positions). The paths are owned copies; they don't change when
+/// Spatial Audio updates its internal state.
+///
+/// The surfaces the paths bounce off are copied before returning, while [Geometry::set] and
+/// [remove_geometry] wait for the copy to be done.
+///
+/// *Return*
+/// > - The paths if successful
+/// > - [AK_IDNotFound](AkResult::AK_IDNotFound) if the game object is not registered
+/// > - [AK_Fail](AkResult::AK_Fail) if Spatial Audio is not initialized
+///
+/// *See also*
+/// > - [query_diffraction_paths]
+pub fn query_reflection_paths(
+    game_obj_id: AkGameObjectID,
+    position_index: AkUInt32,
+    max_paths: usize,
+) -> Result<QueriedPaths<ReflectionPath>, AkResult> {
+    let mut listener_position = AkVector::default();
+    let mut emitter_position = AkVector::default();
+    let mut raw_paths = (0..max_paths)
+        .map(|_| unsafe { std::mem::zeroed::<AkReflectionPathInfo>() })
+        .collect::<Vec<_>>();
+    let mut num_paths = max_paths as AkUInt32;
+
+    // The surfaces of the paths point into Spatial Audio's geometry: keep it from being replaced
+    // or removed until they are copied
+    let _geometry = GEOMETRY_LOCK.lock().unwrap();
+    ak_call_result![QueryReflectionPaths(
+        game_obj_id,
+        position_index,
+        &mut listener_position,
+        &mut emitter_position,
+        raw_paths.as_mut_ptr(),
+        &mut num_paths
+    )]?;
+
+    Ok(QueriedPaths {
+        listener_position,
+        emitter_position,
+        paths: raw_paths
+            .iter()
+            .take(num_paths as usize)
+            // Safety: GEOMETRY_LOCK is held since the query
+            .map(|info| unsafe { ReflectionPath::copy_from(info) })
+            .collect(),
+    })
+}
+
+/// Query the wet diffraction amount of a portal, in `[0, 1]`: how much the reverb of the room
+/// behind the portal is diffracted before it reaches the listener.
+///
+/// *Return*
+/// > - The wet diffraction if successful
+/// > - [AK_IDNotFound](AkResult::AK_IDNotFound) if the portal doesn't exist
+pub fn query_wet_diffraction(portal_id: AkPortalID) -> Result<AkReal32, AkResult> {
+    let mut wet_diffraction = 0.;
+    ak_call_result![QueryWetDiffraction(portal_id, &mut wet_diffraction)]?;
+    Ok(wet_diffraction)
+}
+
+/// A convex volume used by the automatic room assignment to know which game objects are in which
+/// room.
+///
+/// *See also* [add_room_volume]
+#[derive(Debug, Clone)]
+pub enum RoomVolume {
+    /// An axis-aligned box, between its `min` and `max` corners.
+    Aabb { min: AkVector, max: AkVector },
+    /// A convex hull, given by the planes of its faces.
+    ///
+    /// Each plane is given by its normal, pointing outside of the volume, and its distance to the
+    /// origin along that normal: a point `p` is inside the volume when `dot(normal, p) <= distance`
+    /// for all the planes. Use [RoomVolume::convex_hull] to have the normals normalized for you.
+    ConvexHull(Vec<(AkVector, AkReal32)>),
+}
+
+impl RoomVolume {
+    /// A convex hull from the planes of its faces; see [RoomVolume::ConvexHull].
+    ///
+    /// The normals are normalized, and the distances scaled accordingly.
+    pub fn convex_hull<T: Into<AkVector>>(planes: impl IntoIterator<Item = (T, AkReal32)>) -> Self {
+        Self::ConvexHull(
+            planes
+                .into_iter()
+                .map(|(normal, distance)| {
+                    let n = normal.into();
+                    let len = (n.X * n.X + n.Y * n.Y + n.Z * n.Z).sqrt();
+                    (
+                        AkVector {
+                            X: n.X / len,
+                            Y: n.Y / len,
+                            Z: n.Z / len,
+                        },
+                        distance / len,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    /// Signed distance from `p` to the boundary of the volume: negative inside, positive outside.
+    ///
+    /// For convex hulls, this is the distance to the farthest face plane, which is exact inside
+    /// the volume and a lower bound outside of it.
+    pub fn signed_distance(&self, p: &AkVector) -> AkReal32 {
+        match self {
+            Self::Aabb { min, max } => [
+                min.X - p.X,
+                p.X - max.X,
+                min.Y - p.Y,
+                p.Y - max.Y,
+                min.Z - p.Z,
+                p.Z - max.Z,
+            ]
+            .into_iter()
+            .fold(AkReal32::NEG_INFINITY, AkReal32::max),
+            Self::ConvexHull(planes) => planes
+                .iter()
+                .map(|(n, d)| n.X * p.X + n.Y * p.Y + n.Z * p.Z - d)
+                .fold(AkReal32::NEG_INFINITY, AkReal32::max),
+        }
+    }
+
+    /// Whether `p` is inside the volume grown by `margin` (or shrunk, if `margin` is negative).
+    pub fn contains(&self, p: &AkVector, margin: AkReal32) -> bool {
+        self.signed_distance(p) <= margin
+    }
+}
+
+#[derive(Default)]
+struct RoomAssignment {
+    volumes: Vec<(AkRoomID, RoomVolume)>,
+    hysteresis: AkReal32,
+    moved: HashMap<AkGameObjectID, AkVector>,
+    rooms: HashMap<AkGameObjectID, AkRoomID>,
+}
+
+static ROOM_ASSIGNMENT_ENABLED: AtomicBool = AtomicBool::new(false);
+static ROOM_ASSIGNMENT: Mutex<Option<RoomAssignment>> = Mutex::new(None);
+
+/// Enable the automatic room assignment.
+///
+/// Once enabled, the position of every game object set with
+/// [sound_engine::set_position](crate::sound_engine::set_position) is tracked, and
+/// [update_room_assignments] places each game object that moved in the room whose
+/// [volume](RoomVolume) contains it, with [set_game_object_in_room].
+///
+/// `hysteresis` is a distance, in game units, that prevents game objects near walls from
+/// flickering between rooms: a game object enters a room only once it is deeper than `hysteresis`
+/// in it, and leaves it only once it is farther than `hysteresis` outside of it. Rooms are still
+/// evaluated by priority (see [add_room_volume]): a game object enters a room nested in its
+/// current room as soon as it is deeper than `hysteresis` in the nested room.
+///
+/// *See also*
+/// > - [add_room_volume]
+/// > - [update_room_assignments]
+/// > - [disable_automatic_room_assignment]
+pub fn enable_automatic_room_assignment(hysteresis: AkReal32) {
+    let mut assignment = ROOM_ASSIGNMENT.lock().unwrap();
+    assignment
+        .get_or_insert_with(RoomAssignment::default)
+        .hysteresis = hysteresis.abs();
+    ROOM_ASSIGNMENT_ENABLED.store(true, Ordering::SeqCst);
+}
+
+/// Disable the automatic room assignment, and forget all room volumes and tracked game objects.
+///
+/// The game objects stay in the room they were last assigned to.
+///
+/// *See also* [enable_automatic_room_assignment]
+pub fn disable_automatic_room_assignment() {
+    ROOM_ASSIGNMENT_ENABLED.store(false, Ordering::SeqCst);
+    ROOM_ASSIGNMENT.lock().unwrap().take();
+}
+
+/// Add a volume to `room`, for the automatic room assignment.
+///
+/// A room can have several volumes (for instance, an L-shaped room made of two boxes). When
+/// volumes of different rooms overlap, the volume added last wins: add nested rooms after the rooms
+/// that contain them.
+///
+/// *Remark* Volumes can be added before the automatic room assignment is enabled, but game objects
+/// are only placed in rooms once [enable_automatic_room_assignment] has been called.
+///
+/// *See also*
+/// > - [remove_room_volumes]
+/// > - [update_room_assignments]
+pub fn add_room_volume<T: Into<AkRoomID>>(room: T, volume: RoomVolume) {
+    ROOM_ASSIGNMENT
+        .lock()
+        .unwrap()
+        .get_or_insert_with(RoomAssignment::default)
+        .volumes
+        .push((room.into(), volume));
+}
+
+/// Remove all the volumes of `room` from the automatic room assignment.
+///
+/// The game objects in `room` will be reassigned by the next call to [update_room_assignments].
+pub fn remove_room_volumes<T: Into<AkRoomID>>(room: T) {
+    let room = room.into();
+    if let Some(assignment) = ROOM_ASSIGNMENT.lock().unwrap().as_mut() {
+        assignment.volumes.retain(|(r, _)| *r != room);
+
+        let RoomAssignment { moved, rooms, .. } = assignment;
+        for (game_obj_id, _) in rooms.iter().filter(|(_, r)| **r == room) {
+            if let Ok(position) = crate::query_params::get_position(*game_obj_id) {
+                moved.insert(*game_obj_id, position.position);
+            }
+        }
+    }
+}
+
+/// Place each game object that moved since the last call in the room that contains it.
+///
+/// Call this once per frame (for instance, right before
+/// [sound_engine::render_audio](crate::sound_engine::render_audio)) when the automatic room
+/// assignment is enabled. [set_game_object_in_room] is only called for game objects that changed
+/// rooms.
+///
+/// *Return* [AK_Success](AkResult::AK_Success) if all game objects could be placed, otherwise the
+/// last error returned by [set_game_object_in_room]. Game objects that could not be placed are
+/// retried on the next call.
+///
+/// *See also* [enable_automatic_room_assignment]
+pub fn update_room_assignments() -> Result<(), AkResult> {
+    let mut assignment = ROOM_ASSIGNMENT.lock().unwrap();
+    let assignment = match assignment.as_mut() {
+        Some(assignment) => assignment,
+        None => return Ok(()),
+    };
+
+    let mut result = Ok(());
+    let mut failed = HashMap::new();
+    for (game_obj_id, position) in std::mem::take(&mut assignment.moved) {
+        let current = assignment
+            .rooms
+            .get(&game_obj_id)
+            .copied()
+            .unwrap_or_else(AkRoomID::outdoors);
+
+        // Volumes are evaluated from the highest priority one, so that game objects can enter
+        // rooms nested in their current room; hysteresis only favors the current room over the
+        // other candidates
+        let hysteresis = assignment.hysteresis;
+        let new_room = assignment
+            .volumes
+            .iter()
+            .rev()
+            .find(|(room, volume)| {
+                let margin = if *room == current {
+                    hysteresis
+                } else {
+                    -hysteresis
+                };
+                volume.contains(&position, margin)
+            })
+            .map(|(room, _)| *room)
+            .unwrap_or_else(AkRoomID::outdoors);
+
+        if new_room == current {
+            continue;
+        }
+
+        match set_game_object_in_room(game_obj_id, new_room) {
+            Ok(()) => {
+                assignment.rooms.insert(game_obj_id, new_room);
+            }
+            Err(akr) => {
+                log::warn!(
+                    "Could not place game object {} in room {}: {}",
+                    game_obj_id,
+                    new_room.id,
+                    akr
+                );
+                failed.insert(game_obj_id, position);
+                result = Err(akr);
+            }
+        }
+    }
+    assignment.moved.extend(failed);
+
+    result
+}
+
+/// Track the new position of a game object for the automatic room assignment.
+pub(crate) fn track_position(game_obj_id: AkGameObjectID, position: &AkVector) {
+    if ROOM_ASSIGNMENT_ENABLED.load(Ordering::Relaxed) {
+        if let Some(assignment) = ROOM_ASSIGNMENT.lock().unwrap().as_mut() {
+            assignment.moved.insert(game_obj_id, *position);
+        }
+    }
+}
+
+/// Stop tracking a game object for the automatic room assignment; [None] to stop tracking all of
+/// them.
+pub(crate) fn untrack(game_obj_id: Option<AkGameObjectID>) {
+    if ROOM_ASSIGNMENT_ENABLED.load(Ordering::Relaxed) {
+        if let Some(assignment) = ROOM_ASSIGNMENT.lock().unwrap().as_mut() {
+            match game_obj_id {
+                Some(id) => {
+                    assignment.moved.remove(&id);
+                    assignment.rooms.remove(&id);
+                }
+                None => {
+                    assignment.moved.clear();
+                    assignment.rooms.clear();
+                }
+            }
+        }
+    }
+}
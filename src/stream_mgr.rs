@@ -2,9 +2,20 @@
  * Copyright (c) 2022 Contributors to the Rrise project
  */
 
-use crate::bindings::root::{InitDefaultStreamMgr, TermDefaultStreamMgr, AK};
+use crate::bindings::root::{
+    AddDefaultBasePath, DestroyStreamMgr, InitDefaultStreamMgr, IsStreamMgrInitialized,
+    LoadDefaultFilePackage, TermDefaultStreamMgr, UnloadAllDefaultFilePackages,
+    UnloadDefaultFilePackage, AK,
+};
+use crate::low_level_io::{self, EmbeddedIo, LowLevelIo};
 use crate::settings::{AkDeviceSettings, AkStreamMgrSettings};
-use crate::{ak_call_result, to_os_char, AkResult};
+use crate::{ak_call_result, from_os_char, to_os_char, AkResult, AkUInt32};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Base paths of the default streaming manager, in search order.
+static BASE_PATHS: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
 
 /// Stream Manager factory.
 ///
@@ -26,18 +37,60 @@ pub fn init(settings: &AkStreamMgrSettings) -> Result<(), AkResult> {
     }
 }
 
+/// Query whether the Stream Manager was successfully created, by [init] or by one of the
+/// functions initializing a streaming manager.
+///
+/// *Return* True if the Stream Manager is initialized, False otherwise
+pub fn is_initialized() -> bool {
+    unsafe { IsStreamMgrInitialized() }
+}
+
+/// Destroy the Stream Manager only, leaving its streaming devices, if any, to their own term
+/// function.
+fn destroy() {
+    unsafe {
+        DestroyStreamMgr();
+    }
+}
+
 /// Initializes the default streaming manager, specifying the folder in which to find the generated soundbanks when they are loaded.
+///
+/// If the streaming device fails to initialize, the Stream Manager created by this function is
+/// destroyed before returning. Once [term_default_stream_mgr] has been called, the default streaming
+/// manager can be initialized again.
+///
+/// *Return*
+/// > - [AK_Success](AkResult::AK_Success) if successful
+/// > - [AK_Fail](AkResult::AK_Fail) if a Stream Manager is already initialized, which is left
+/// untouched, or if the streaming device couldn't be initialized
+///
+/// *See also* [add_base_path] to search other folders before `bank_location`.
 pub fn init_default_stream_mgr<T: AsRef<str>>(
     stream_mgr_settings: &AkStreamMgrSettings,
     device_settings: &mut AkDeviceSettings,
     bank_location: T,
 ) -> Result<(), AkResult> {
+    if is_initialized() {
+        log::error!(
+            "Can't initialize the default streaming manager: a Stream Manager already exists"
+        );
+        return Err(AkResult::AK_Fail);
+    }
+
     init(stream_mgr_settings)?;
     device_settings.use_stream_cache = true;
 
     let device_settings = device_settings.as_ak();
     let pin_bytes = to_os_char(&bank_location);
-    ak_call_result![InitDefaultStreamMgr(&device_settings, pin_bytes.as_ptr())]
+    // The device cleans itself up when it fails to initialize: only the Stream Manager created
+    // above is left to destroy
+    ak_call_result![InitDefaultStreamMgr(&device_settings, pin_bytes.as_ptr())].map_err(|akr| {
+        destroy();
+        akr
+    })?;
+
+    *BASE_PATHS.lock().unwrap() = vec![PathBuf::from(bank_location.as_ref())];
+    Ok(())
 }
 
 /// Terminates the default streaming manager.
@@ -45,6 +98,200 @@ pub fn term_default_stream_mgr() {
     unsafe {
         TermDefaultStreamMgr();
     }
+    BASE_PATHS.lock().unwrap().clear();
+}
+
+/// Add a folder in which the default streaming manager looks for banks and streamed media,
+/// before all the folders added so far.
+///
+/// Folders are searched from the most recently added one to the `bank_location` given to
+/// [init_default_stream_mgr], which is searched last. Language-specific files are looked for in
+/// the [current language](current_language) subfolder of each of them. To have patches win over
+/// DLCs, and DLCs over the base game, add the DLC folders and then the patch folders.
+///
+/// *Return*
+/// > - [AK_Success](AkResult::AK_Success) if successful
+/// > - [AK_Fail](AkResult::AK_Fail) if the default streaming manager is not initialized, or
+/// another error if the path is invalid or too long
+///
+/// *See also*
+/// > - [base_paths]
+/// > - [resolve_file]
+pub fn add_base_path<T: AsRef<str>>(path: T) -> Result<(), AkResult> {
+    let os_path = to_os_char(&path);
+    // Locked across the call so that concurrent additions are mirrored in the same order
+    let mut base_paths = BASE_PATHS.lock().unwrap();
+    ak_call_result![AddDefaultBasePath(os_path.as_ptr())]?;
+
+    base_paths.insert(0, PathBuf::from(path.as_ref()));
+    log::info!(
+        "Stream manager search order: {}",
+        base_paths
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join(" > ")
+    );
+    Ok(())
+}
+
+/// The folders in which the default streaming manager looks for files, in search order.
+///
+/// *See also* [add_base_path]
+pub fn base_paths() -> Vec<PathBuf> {
+    BASE_PATHS.lock().unwrap().clone()
+}
+
+/// The path of the loose file the default streaming manager opens for `file_name`, found in the
+/// first of the [base_paths] containing it.
+///
+/// If `is_language_specific` is set, the file is looked for in the
+/// [current language](current_language) subfolder of the base paths, like localized banks and
+/// media are. The candidates are logged at the trace level, the result at the debug level.
+///
+/// *Return* [None] if no base path contains the file.
+///
+/// *Remark* Files found in [file packages](load_file_package) take priority over loose files, but
+/// are not considered here.
+pub fn resolve_file<T: AsRef<str>>(file_name: T, is_language_specific: bool) -> Option<PathBuf> {
+    let file_name = file_name.as_ref();
+    let language = if is_language_specific {
+        Some(current_language())
+    } else {
+        None
+    };
+
+    let resolved = BASE_PATHS.lock().unwrap().iter().find_map(|base_path| {
+        let path = match &language {
+            Some(language) => base_path.join(language).join(file_name),
+            None => base_path.join(file_name),
+        };
+        log::trace!("Looking for {} in {}", file_name, path.display());
+        Some(path).filter(|path| Path::is_file(path))
+    });
+
+    match &resolved {
+        Some(path) => log::debug!("Resolved {} to {}", file_name, path.display()),
+        None => log::debug!("Couldn't resolve {} in any base path", file_name),
+    }
+    resolved
+}
+
+/// ID of a file package loaded with [load_file_package].
+pub type PackageId = AkUInt32;
+
+/// Load a file package (`.pck` file generated by the File Packager) in the default streaming
+/// manager.
+///
+/// The package is opened from the folder given to [init_default_stream_mgr]. Its banks and
+/// streamed media are then found by name or by ID like loose files.
+///
+/// *Return*
+/// > - The ID of the package, to unload it with [unload_file_package], if successful
+/// > - [AK_InvalidFile](AkResult::AK_InvalidFile) if the file is not a valid file package
+/// > - [AK_InsufficientMemory](AkResult::AK_InsufficientMemory) if there is not enough memory to load its header
+/// > - [AK_Fail](AkResult::AK_Fail) if the default streaming manager is not initialized, or for any other reason
+///
+/// *Remarks*
+/// > - Packages take priority over loose files: when a file is opened, the loaded packages are
+/// searched first, most recently loaded first, before the base path.
+/// > - Only available with [init_default_stream_mgr].
+///
+/// *See also*
+/// > - [unload_file_package]
+/// > - [unload_all_file_packages]
+pub fn load_file_package<T: AsRef<str>>(name: T) -> Result<PackageId, AkResult> {
+    let mut package_id = 0;
+    let name = to_os_char(name);
+    ak_call_result![LoadDefaultFilePackage(name.as_ptr(), &mut package_id) => package_id]
+}
+
+/// Unload a file package loaded with [load_file_package].
+///
+/// *Return* [AK_Success](AkResult::AK_Success) if successful, [AK_Fail](AkResult::AK_Fail)
+/// otherwise.
+///
+/// *Remark* Files of the package that are still open are not closed: make sure the banks and
+/// streamed media it contains are not used anymore.
+pub fn unload_file_package(package_id: PackageId) -> Result<(), AkResult> {
+    ak_call_result![UnloadDefaultFilePackage(package_id)]
+}
+
+/// Unload all the file packages loaded with [load_file_package].
+///
+/// *Return* [AK_Success](AkResult::AK_Success) if successful, [AK_Fail](AkResult::AK_Fail)
+/// otherwise.
+pub fn unload_all_file_packages() -> Result<(), AkResult> {
+    ak_call_result![UnloadAllDefaultFilePackages()]
+}
+
+/// Initializes a streaming manager reading files through `io`, a Low-Level I/O implemented in
+/// Rust.
+///
+/// `io` is dropped when [term_custom_stream_mgr] is called. Unlike [init_default_stream_mgr], the
+/// stream cache is only enabled if [use_stream_cache](AkDeviceSettings::use_stream_cache) is set.
+///
+/// If the streaming device fails to initialize, the Stream Manager created by this function is
+/// destroyed before returning.
+///
+/// *Return*
+/// > - [AK_Success](AkResult::AK_Success) if successful
+/// > - [AK_InvalidParameter](AkResult::AK_InvalidParameter) if `device_settings` doesn't use the
+/// blocking scheduler
+/// > - [AK_Fail](AkResult::AK_Fail) if a Stream Manager or a [LowLevelIo] is already installed,
+/// which is left untouched, or if the streaming device couldn't be initialized
+///
+/// *See also* [LowLevelIo]
+pub fn init_custom_stream_mgr<IO: LowLevelIo>(
+    stream_mgr_settings: &AkStreamMgrSettings,
+    device_settings: &mut AkDeviceSettings,
+    io: IO,
+) -> Result<(), AkResult> {
+    init_boxed_stream_mgr(stream_mgr_settings, device_settings, Box::new(io))
+}
+
+pub(crate) fn init_boxed_stream_mgr(
+    stream_mgr_settings: &AkStreamMgrSettings,
+    device_settings: &mut AkDeviceSettings,
+    io: Box<dyn LowLevelIo>,
+) -> Result<(), AkResult> {
+    if is_initialized() || low_level_io::is_installed() {
+        log::error!(
+            "Can't initialize the custom streaming manager: a Stream Manager already exists"
+        );
+        return Err(AkResult::AK_Fail);
+    }
+
+    init(stream_mgr_settings)?;
+    // The device cleans itself up when it fails to initialize: only the Stream Manager created
+    // above is left to destroy
+    low_level_io::init_device(device_settings, io).map_err(|akr| {
+        destroy();
+        akr
+    })
+}
+
+/// Terminates the streaming manager initialized with [init_custom_stream_mgr], then drops its
+/// [LowLevelIo].
+pub fn term_custom_stream_mgr() {
+    low_level_io::term_device();
+}
+
+/// Initializes a streaming manager serving `files`, embedded in the executable, by path relative
+/// to the bank folder.
+///
+/// Both banks and streamed media can be served this way; see [EmbeddedIo] for how files are
+/// looked up. Terminate it with [term_custom_stream_mgr].
+///
+/// *Return* See [init_custom_stream_mgr].
+///
+/// *See also* [embed_files!](crate::embed_files)
+pub fn init_embedded_stream_mgr(
+    stream_mgr_settings: &AkStreamMgrSettings,
+    device_settings: &mut AkDeviceSettings,
+    files: HashMap<&'static str, &'static [u8]>,
+) -> Result<(), AkResult> {
+    init_custom_stream_mgr(stream_mgr_settings, device_settings, EmbeddedIo::new(files))
 }
 
 /// Set the current language once and only once, here. The language name is stored in a static buffer
@@ -55,9 +302,8 @@ pub fn term_default_stream_mgr() {
 ///
 /// Pass a string, without a trailing slash or backslash. Empty strings are accepted.
 ///
-/// You may register for language changes (see [register_to_language_change_notification]).
-///
-/// After changing the current language, all observers are notified.
+/// To find the available languages, reload the localized banks and notify observers when the
+/// language changes, use a [LanguageManager](crate::language::LanguageManager) instead.
 ///
 /// *Return* [AK_Success](AkResult::AK_Success) if successful (if language string has less than
 /// AK_MAX_LANGUAGE_NAME_SIZE characters). [AK_Fail](AkResult::AK_Fail) otherwise.
@@ -66,8 +312,15 @@ pub fn term_default_stream_mgr() {
 ///
 /// *See also*
 /// - [current_language]
-/// - [add_language_change_observer]
+/// - [LanguageManager::set_language](crate::language::LanguageManager::set_language)
 pub fn set_current_language<T: AsRef<str>>(language_name: T) -> Result<(), AkResult> {
     let pin_bytes = to_os_char(&language_name);
     ak_call_result![AK::StreamMgr::SetCurrentLanguage(pin_bytes.as_ptr())]
 }
+
+/// Get the current language, as set with [set_current_language].
+///
+/// *Return* The current language, empty if none was set.
+pub fn current_language() -> String {
+    unsafe { from_os_char(AK::StreamMgr::GetCurrentLanguage()) }
+}
@@ -0,0 +1,87 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+use rrise::spatial_audio::{AcousticSurface, Geometry, GeometryError, Triangle};
+use rrise::AkVector;
+
+fn vertices(scale: f32) -> Vec<AkVector> {
+    vec![
+        [0., 0., 0.].into(),
+        [scale, 0., 0.].into(),
+        [0., scale, 0.].into(),
+        [2. * scale, 0., 0.].into(),
+    ]
+}
+
+/// Tests whether valid geometry passes validation, whatever its scale.
+#[test]
+fn valid_geometry() {
+    let triangles = [Triangle::new([0, 1, 2], None)];
+    for scale in [1e-4, 1., 1e4] {
+        let vertices = vertices(scale);
+        assert_eq!(Geometry::new(0, &vertices, &triangles).validate(), Ok(()));
+    }
+}
+
+/// Tests whether invalid triangles are reported with the index of the faulty triangle.
+#[test]
+fn invalid_triangles() {
+    let vertices = vertices(1.);
+
+    let triangles = [
+        Triangle::new([0, 1, 2], None),
+        Triangle::new([0, 1, 4], None),
+    ];
+    assert_eq!(
+        Geometry::new(0, &vertices, &triangles).validate(),
+        Err(GeometryError::VertexOutOfRange {
+            triangle: 1,
+            vertex: 4
+        })
+    );
+
+    let triangles = [Triangle::new([0, 1, 2], Some(0))];
+    assert_eq!(
+        Geometry::new(0, &vertices, &triangles).validate(),
+        Err(GeometryError::SurfaceOutOfRange {
+            triangle: 0,
+            surface: 0
+        })
+    );
+
+    for points in [[0, 1, 3], [0, 0, 2]] {
+        let triangles = [Triangle::new(points, None)];
+        assert_eq!(
+            Geometry::new(0, &vertices, &triangles).validate(),
+            Err(GeometryError::DegenerateTriangle { triangle: 0 })
+        );
+    }
+}
+
+/// Tests whether invalid vertices and surface names are reported.
+#[test]
+fn invalid_vertices_and_surfaces() {
+    let mut vertices = vertices(1.);
+    let triangles = [Triangle::new([0, 1, 2], Some(1))];
+
+    let surfaces = [
+        AcousticSurface::new(0),
+        AcousticSurface {
+            name: Some("wall\0"),
+            ..AcousticSurface::new(0)
+        },
+    ];
+    assert_eq!(
+        Geometry::new(0, &vertices, &triangles)
+            .with_surfaces(&surfaces)
+            .validate(),
+        Err(GeometryError::InvalidSurfaceName { surface: 1 })
+    );
+
+    vertices[3].Y = f32::NAN;
+    assert_eq!(
+        Geometry::new(0, &vertices, &triangles).validate(),
+        Err(GeometryError::InvalidVertex { vertex: 3 })
+    );
+}
@@ -0,0 +1,30 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+mod common;
+
+use rrise::engine::{Engine, EngineStep};
+use rrise::{sound_engine, AkResult};
+
+/// Tests whether Rrise can be initialized again in the same process after being terminated, and
+/// refuses to initialize twice at the same time.
+#[test]
+fn reinit() -> Result<(), AkResult> {
+    common::one_frame_render()?;
+
+    {
+        let _engine = common::init_sound_engine()?;
+        assert!(Engine::is_alive());
+
+        let second = common::init_sound_engine();
+        assert_eq!(second.unwrap_err().step, EngineStep::Engine);
+        assert!(sound_engine::is_initialized());
+
+        sound_engine::render_audio(false)?;
+    }
+    assert!(!Engine::is_alive());
+    assert!(!sound_engine::is_initialized());
+
+    common::one_frame_render()
+}
@@ -0,0 +1,28 @@
+/*
+ * Copyright (c) 2022 Contributors to the Rrise project
+ */
+
+mod common;
+
+use rrise::settings::{AkDeviceSettings, AkStreamMgrSettings};
+use rrise::{sound_engine, stream_mgr, AkResult};
+
+/// Tests whether initializing the default streaming manager a second time fails without tearing
+/// down the one in use, so that banks can still be loaded.
+#[test]
+fn stream_mgr_double_init() -> Result<(), AkResult> {
+    let _engine = common::init_sound_engine()?;
+    assert!(stream_mgr::is_initialized());
+
+    let second = stream_mgr::init_default_stream_mgr(
+        &AkStreamMgrSettings::default(),
+        &mut AkDeviceSettings::default(),
+        "examples/WwiseProject/GeneratedSoundBanks",
+    );
+    assert_eq!(second, Err(AkResult::AK_Fail));
+    assert!(stream_mgr::is_initialized());
+
+    sound_engine::load_bank_by_name("Init.bnk")?;
+    sound_engine::render_audio(false)?;
+    Ok(())
+}